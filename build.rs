@@ -0,0 +1,4 @@
+fn main() {
+    // Rebuild when a migration is added, `sqlx::migrate!` embeds them at compile time
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,68 @@
+CREATE TABLE IF NOT EXISTS logs (
+    id                 VARCHAR(64)  NOT NULL PRIMARY KEY,
+    created_at         VARCHAR(32)  NOT NULL,
+    group_id           VARCHAR(64)  NOT NULL,
+    actor_id           VARCHAR(64)  NOT NULL,
+    actor_display_name VARCHAR(255) NULL,
+    target_id          VARCHAR(64)  NULL,
+    event_type         VARCHAR(64)  NOT NULL,
+    description        TEXT         NOT NULL,
+    data               JSON         NOT NULL
+);
+
+-- Separate statements so tables created before the migrations get the indexes too,
+-- MySQL has no CREATE INDEX IF NOT EXISTS so each one checks for an existing index first
+SET @statement = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+        WHERE table_schema = DATABASE() AND table_name = 'logs'
+        AND index_name = 'logs_target_id') = 0,
+    'CREATE INDEX logs_target_id ON logs (target_id)',
+    'DO 0'
+);
+PREPARE statement FROM @statement;
+EXECUTE statement;
+DEALLOCATE PREPARE statement;
+
+SET @statement = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+        WHERE table_schema = DATABASE() AND table_name = 'logs'
+        AND index_name = 'logs_event_type') = 0,
+    'CREATE INDEX logs_event_type ON logs (event_type)',
+    'DO 0'
+);
+PREPARE statement FROM @statement;
+EXECUTE statement;
+DEALLOCATE PREPARE statement;
+
+SET @statement = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+        WHERE table_schema = DATABASE() AND table_name = 'logs'
+        AND index_name = 'logs_actor_id') = 0,
+    'CREATE INDEX logs_actor_id ON logs (actor_id)',
+    'DO 0'
+);
+PREPARE statement FROM @statement;
+EXECUTE statement;
+DEALLOCATE PREPARE statement;
+
+SET @statement = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+        WHERE table_schema = DATABASE() AND table_name = 'logs'
+        AND index_name = 'logs_created_at') = 0,
+    'CREATE INDEX logs_created_at ON logs (created_at)',
+    'DO 0'
+);
+PREPARE statement FROM @statement;
+EXECUTE statement;
+DEALLOCATE PREPARE statement;
+
+SET @statement = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+        WHERE table_schema = DATABASE() AND table_name = 'logs'
+        AND index_name = 'logs_group_id_created_at') = 0,
+    'CREATE INDEX logs_group_id_created_at ON logs (group_id, created_at)',
+    'DO 0'
+);
+PREPARE statement FROM @statement;
+EXECUTE statement;
+DEALLOCATE PREPARE statement;
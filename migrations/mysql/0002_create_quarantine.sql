@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS quarantine (
+    id             VARCHAR(64) NOT NULL PRIMARY KEY,
+    group_id       VARCHAR(64) NOT NULL,
+    entry          JSON        NOT NULL,
+    error          TEXT        NOT NULL,
+    quarantined_at TIMESTAMP   NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+UPDATE logs SET created_at = REPLACE(REPLACE(created_at, 'T', ' '), 'Z', '');
+
+ALTER TABLE logs MODIFY created_at DATETIME(3) NOT NULL;
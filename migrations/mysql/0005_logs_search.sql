@@ -0,0 +1,3 @@
+ALTER TABLE logs ADD COLUMN data_text LONGTEXT AS (CAST(data AS CHAR)) STORED;
+
+ALTER TABLE logs ADD FULLTEXT INDEX logs_search (description, data_text);
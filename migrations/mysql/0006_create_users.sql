@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS users (
+    id           VARCHAR(64)  NOT NULL PRIMARY KEY,
+    display_name VARCHAR(255) NOT NULL,
+    user_icon    TEXT         NOT NULL,
+    thumbnail    TEXT         NOT NULL,
+    bio          TEXT         NOT NULL,
+    badges       JSON         NOT NULL,
+    updated_at   DATETIME(3)  NOT NULL,
+
+    INDEX users_updated_at (updated_at)
+);
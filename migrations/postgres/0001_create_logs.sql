@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS logs (
+    id                 VARCHAR(64)  NOT NULL PRIMARY KEY,
+    created_at         VARCHAR(32)  NOT NULL,
+    group_id           VARCHAR(64)  NOT NULL,
+    actor_id           VARCHAR(64)  NOT NULL,
+    actor_display_name VARCHAR(255) NULL,
+    target_id          VARCHAR(64)  NULL,
+    event_type         VARCHAR(64)  NOT NULL,
+    description        TEXT         NOT NULL,
+    data               JSONB        NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS logs_target_id ON logs (target_id);
+CREATE INDEX IF NOT EXISTS logs_event_type ON logs (event_type);
+CREATE INDEX IF NOT EXISTS logs_actor_id ON logs (actor_id);
+CREATE INDEX IF NOT EXISTS logs_created_at ON logs (created_at);
+CREATE INDEX IF NOT EXISTS logs_group_id_created_at ON logs (group_id, created_at);
+CREATE INDEX IF NOT EXISTS logs_data ON logs USING GIN (data);
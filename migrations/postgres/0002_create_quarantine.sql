@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS quarantine (
+    id             VARCHAR(64) NOT NULL PRIMARY KEY,
+    group_id       VARCHAR(64) NOT NULL,
+    entry          JSONB       NOT NULL,
+    error          TEXT        NOT NULL,
+    quarantined_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1 @@
+ALTER TABLE logs ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at::TIMESTAMPTZ;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS logs_group_id_target_id_created_at ON logs (group_id, target_id, created_at);
@@ -0,0 +1,4 @@
+ALTER TABLE logs ADD COLUMN search TSVECTOR
+    GENERATED ALWAYS AS (to_tsvector('english', description || ' ' || data::TEXT)) STORED;
+
+CREATE INDEX IF NOT EXISTS logs_search ON logs USING GIN (search);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS users (
+    id           VARCHAR(64)  NOT NULL PRIMARY KEY,
+    display_name VARCHAR(255) NOT NULL,
+    user_icon    TEXT         NOT NULL,
+    thumbnail    TEXT         NOT NULL,
+    bio          TEXT         NOT NULL,
+    badges       JSONB        NOT NULL,
+    updated_at   TIMESTAMPTZ  NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS users_updated_at ON users (updated_at);
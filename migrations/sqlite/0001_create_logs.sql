@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS logs (
+    id                 TEXT NOT NULL PRIMARY KEY,
+    created_at         TEXT NOT NULL,
+    group_id           TEXT NOT NULL,
+    actor_id           TEXT NOT NULL,
+    actor_display_name TEXT NULL,
+    target_id          TEXT NULL,
+    event_type         TEXT NOT NULL,
+    description        TEXT NOT NULL,
+    data               TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS logs_target_id ON logs (target_id);
+CREATE INDEX IF NOT EXISTS logs_event_type ON logs (event_type);
+CREATE INDEX IF NOT EXISTS logs_actor_id ON logs (actor_id);
+CREATE INDEX IF NOT EXISTS logs_created_at ON logs (created_at);
+CREATE INDEX IF NOT EXISTS logs_group_id_created_at ON logs (group_id, created_at);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS quarantine (
+    id             TEXT NOT NULL PRIMARY KEY,
+    group_id       TEXT NOT NULL,
+    entry          TEXT NOT NULL,
+    error          TEXT NOT NULL,
+    quarantined_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
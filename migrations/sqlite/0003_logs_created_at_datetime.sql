@@ -0,0 +1,34 @@
+-- SQLite can't change a column type in place, so the table is rebuilt
+CREATE TABLE logs_new (
+    id                 TEXT     NOT NULL PRIMARY KEY,
+    created_at         DATETIME NOT NULL,
+    group_id           TEXT     NOT NULL,
+    actor_id           TEXT     NOT NULL,
+    actor_display_name TEXT     NULL,
+    target_id          TEXT     NULL,
+    event_type         TEXT     NOT NULL,
+    description        TEXT     NOT NULL,
+    data               TEXT     NOT NULL
+);
+
+-- Normalise to fixed-width UTC text (milliseconds) so the dates sort chronologically
+INSERT INTO logs_new
+SELECT
+    id,
+    strftime('%Y-%m-%dT%H:%M:%fZ', created_at),
+    group_id,
+    actor_id,
+    actor_display_name,
+    target_id,
+    event_type,
+    description,
+    data
+FROM logs;
+DROP TABLE logs;
+ALTER TABLE logs_new RENAME TO logs;
+
+CREATE INDEX IF NOT EXISTS logs_target_id ON logs (target_id);
+CREATE INDEX IF NOT EXISTS logs_event_type ON logs (event_type);
+CREATE INDEX IF NOT EXISTS logs_actor_id ON logs (actor_id);
+CREATE INDEX IF NOT EXISTS logs_created_at ON logs (created_at);
+CREATE INDEX IF NOT EXISTS logs_group_id_created_at ON logs (group_id, created_at);
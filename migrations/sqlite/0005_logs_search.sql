@@ -0,0 +1,22 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS logs_fts USING fts5 (
+    description,
+    data,
+    content = 'logs',
+    content_rowid = 'rowid'
+);
+
+-- Keep the external content index in sync with the logs table
+CREATE TRIGGER IF NOT EXISTS logs_fts_insert AFTER INSERT ON logs BEGIN
+    INSERT INTO logs_fts (rowid, description, data) VALUES (new.rowid, new.description, new.data);
+END;
+
+CREATE TRIGGER IF NOT EXISTS logs_fts_delete AFTER DELETE ON logs BEGIN
+    INSERT INTO logs_fts (logs_fts, rowid, description, data) VALUES ('delete', old.rowid, old.description, old.data);
+END;
+
+CREATE TRIGGER IF NOT EXISTS logs_fts_update AFTER UPDATE ON logs BEGIN
+    INSERT INTO logs_fts (logs_fts, rowid, description, data) VALUES ('delete', old.rowid, old.description, old.data);
+    INSERT INTO logs_fts (rowid, description, data) VALUES (new.rowid, new.description, new.data);
+END;
+
+INSERT INTO logs_fts (logs_fts) VALUES ('rebuild');
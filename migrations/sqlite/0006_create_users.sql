@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS users (
+    id           TEXT     NOT NULL PRIMARY KEY,
+    display_name TEXT     NOT NULL,
+    user_icon    TEXT     NOT NULL,
+    thumbnail    TEXT     NOT NULL,
+    bio          TEXT     NOT NULL,
+    badges       TEXT     NOT NULL,
+    updated_at   DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS users_updated_at ON users (updated_at);
@@ -14,7 +14,13 @@ pub async fn help(ctx: Context<'_, Data, Error>) -> Result<()> {
     .author(CreateEmbedAuthor::new("").name("Shayne Hartford (ShayBox)").url("https://shaybox.com").icon_url("https://avatars1.githubusercontent.com/u/9505196"))
     .field("Commands", "", false)
     .field("User", "Manage a VRChat user", true)
+    .field("History", "Moderation timeline of a VRChat user", true)
     .field("Help", "Information about VRC-BAN", true)
+    .field("Leaderboard", "Staff member moderation rankings", true)
+    .field("Staff", "Review the staff members actions", true)
+    .field("Logs", "Search and export the audit logs", true)
+    .field("Purge", "Purge the audit logs past their retention", true)
+    .field("Sync", "Manage the audit log sync", true)
     .footer(CreateEmbedFooter::new("VRC-BAN").icon_url("https://cdn.discordapp.com/avatars/1208696990284914719/ab66b12988c0b0ba0e70405abe8089b6"));
 
     let builder = CreateReply::default().embed(embed).ephemeral(true);
@@ -0,0 +1,61 @@
+use color_eyre::{
+    eyre::{bail, Error},
+    Result,
+};
+use poise::{serenity_prelude::*, Context};
+
+use super::{
+    paginate::{create_log_pages, format_log_by_actor, paginate},
+    util::find_group,
+};
+use crate::{users, Data};
+
+/// Full moderation timeline of a user
+#[poise::command(slash_command, guild_only, required_permissions = "BAN_MEMBERS")]
+pub async fn history(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Search by User Name"] name: Option<String>,
+    #[description = "Search by User UUID"] uuid: Option<String>,
+) -> Result<()> {
+    ctx.defer().await?;
+
+    let Data {
+        config: _,
+        logsdb,
+        vrchat,
+        sync: _,
+    } = ctx.data();
+
+    let group = find_group(ctx)?;
+
+    /* Parse the moderator input (name, uuid) */
+    let (user_id, display_name) = if let Some(search) = name {
+        let users = vrchat.search_users(&search).await?;
+        let Some(user) = users.into_iter().next() else {
+            bail!("No user found")
+        };
+
+        (user.id, user.display_name)
+    } else if let Some(user_id) = uuid {
+        let user = users::get_user(logsdb.as_ref(), vrchat, &user_id).await?;
+
+        (user.id, user.display_name)
+    } else {
+        bail!("Search by User Name or UUID")
+    };
+
+    let logs = logsdb.get_history(&group.group_id, &user_id).await?;
+    if logs.is_empty() {
+        bail!("No history found for {display_name}")
+    }
+
+    /* Paginate the timeline (newest first) */
+    let author =
+        CreateEmbedAuthor::new(display_name).url(format!("https://vrchat.com/home/user/{user_id}"));
+    let embed = CreateEmbed::default()
+        .author(author)
+        .title("Moderation History");
+    let pages = create_log_pages(&logs, &embed, "events", format_log_by_actor);
+
+    paginate(ctx, pages).await
+}
@@ -0,0 +1,107 @@
+use color_eyre::{
+    eyre::{bail, Error},
+    Result,
+};
+use poise::{serenity_prelude::*, ChoiceParameter, Context};
+use time::OffsetDateTime;
+
+use super::{paginate::paginate, util::find_group};
+use crate::{logsdb::StaffStats, Data};
+
+/// Staff members per leaderboard page
+const PAGE_SIZE: usize = 10;
+
+#[derive(ChoiceParameter, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Action {
+    #[default]
+    Bans,
+    Kicks,
+    Warns,
+}
+
+impl Action {
+    /// # Get the staff members all-time and recent action counts
+    const fn count(self, stats: &StaffStats) -> (i64, i64) {
+        match self {
+            Self::Bans => (stats.all_bans, stats.new_bans),
+            Self::Kicks => (stats.all_kick, stats.new_kick),
+            Self::Warns => (stats.all_warn, stats.new_warn),
+        }
+    }
+}
+
+/// Staff member moderation leaderboard
+#[poise::command(slash_command, guild_only)]
+pub async fn leaderboard(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Rank by action type"] action: Option<Action>,
+    #[description = "Rank by recent actions"] recent: Option<bool>,
+) -> Result<()> {
+    ctx.defer().await?;
+
+    let Data {
+        config,
+        logsdb,
+        vrchat: _,
+        sync: _,
+    } = ctx.data();
+
+    let group = find_group(ctx)?;
+
+    /* Rank the staff members by the action type */
+    let action = action.unwrap_or_default();
+    let recent = recent.unwrap_or_default();
+    let days = config.leaderboard_recent_days;
+    let since = OffsetDateTime::now_utc() - time::Duration::days(days);
+    let mut stats = logsdb
+        .get_staff_stats(&group.group_id, since)
+        .await?
+        .into_iter()
+        .filter(|stats| action.count(stats).0 > 0)
+        .collect::<Vec<_>>();
+
+    stats.sort_by_key(|stats| {
+        let (all, new) = action.count(stats);
+        std::cmp::Reverse(if recent { (new, all) } else { (all, new) })
+    });
+
+    if stats.is_empty() {
+        bail!("No {} found", action.name().to_lowercase())
+    }
+
+    /* Paginate the rankings */
+    let chunks = stats.chunks(PAGE_SIZE).collect::<Vec<_>>();
+    let pages = chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let description = chunk
+                .iter()
+                .enumerate()
+                .map(|(rank, stats)| {
+                    let (all, new) = action.count(stats);
+                    let url = format!("https://vrchat.com/home/user/{}", stats.actor_id);
+                    let rank = index * PAGE_SIZE + rank + 1;
+                    format!(
+                        "**{rank}.** [{}]({url}) - {all} ({new} recent)",
+                        stats.username
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let footer = format!(
+                "Page {}/{} - Recent is the last {days} days",
+                index + 1,
+                chunks.len()
+            );
+
+            CreateEmbed::default()
+                .title(format!("{} Leaderboard", action.name()))
+                .description(description)
+                .footer(CreateEmbedFooter::new(footer))
+        })
+        .collect();
+
+    paginate(ctx, pages).await
+}
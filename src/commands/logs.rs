@@ -0,0 +1,139 @@
+use color_eyre::{
+    eyre::{bail, Error},
+    Result,
+};
+use poise::{serenity_prelude::*, Context, CreateReply};
+
+use super::{
+    paginate::{create_log_pages, format_log_by_actor, paginate},
+    util::{autocomplete_event_type, find_group},
+};
+use crate::{
+    date::{parse_date, parse_end_date},
+    event::EventType,
+    export::{self, ExportFormat},
+    storage::LogFilter,
+    Data,
+};
+
+/// Maximum search results
+const LIMIT: i64 = 100;
+
+/// Discord attachment size limit (bytes) without server boosts
+const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Browse the audit logs
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "BAN_MEMBERS",
+    subcommands("search", "export"),
+    subcommand_required
+)]
+pub async fn logs(_ctx: Context<'_, Data, Error>) -> Result<()> {
+    Ok(())
+}
+
+/// Search the audit log descriptions and data
+#[poise::command(slash_command, guild_only, required_permissions = "BAN_MEMBERS")]
+async fn search(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Search terms (names, reasons, roles)"] query: String,
+    #[description = "Only this event type"]
+    #[autocomplete = "autocomplete_event_type"]
+    event_type: Option<String>,
+    #[description = "Start date (YYYY-MM-DD or RFC 3339)"] start: Option<String>,
+    #[description = "End date (YYYY-MM-DD includes the day, or RFC 3339)"] end: Option<String>,
+) -> Result<()> {
+    ctx.defer().await?;
+
+    let Data {
+        config: _,
+        logsdb,
+        vrchat: _,
+        sync: _,
+    } = ctx.data();
+
+    let group = find_group(ctx)?;
+
+    let event_type = event_type.as_deref().map(EventType::from);
+    let start = start.as_deref().map(parse_date).transpose()?;
+    let end = end.as_deref().map(parse_end_date).transpose()?;
+    let logs = logsdb
+        .search_logs(
+            &group.group_id,
+            &query,
+            event_type.as_ref(),
+            start,
+            end,
+            LIMIT,
+        )
+        .await?;
+
+    if logs.is_empty() {
+        bail!("No logs found for {query}")
+    }
+
+    /* Paginate the results (newest first) */
+    let embed = CreateEmbed::default().title(format!("Search: {query}"));
+    let pages = create_log_pages(&logs, &embed, "results", format_log_by_actor);
+
+    paginate(ctx, pages).await
+}
+
+/// Export the audit logs as a file
+#[poise::command(slash_command, guild_only, required_permissions = "BAN_MEMBERS")]
+async fn export(
+    ctx: Context<'_, Data, Error>,
+    #[description = "File format (default: CSV)"] format: Option<ExportFormat>,
+    #[description = "Only this event type"]
+    #[autocomplete = "autocomplete_event_type"]
+    event_type: Option<String>,
+    #[description = "Only actions by this User UUID"] actor: Option<String>,
+    #[description = "Only actions on this User UUID"] target: Option<String>,
+    #[description = "Start date (YYYY-MM-DD or RFC 3339)"] start: Option<String>,
+    #[description = "End date (YYYY-MM-DD includes the day, or RFC 3339)"] end: Option<String>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let Data {
+        config: _,
+        logsdb,
+        vrchat: _,
+        sync: _,
+    } = ctx.data();
+
+    let group = find_group(ctx)?;
+
+    let filter = LogFilter {
+        group_id:   Some(group.group_id.clone()),
+        event_type: event_type.as_deref().map(EventType::from),
+        actor_id:   actor,
+        target_id:  target,
+        start:      start.as_deref().map(parse_date).transpose()?,
+        end:        end.as_deref().map(parse_end_date).transpose()?,
+    };
+
+    let format = format.unwrap_or_default();
+    let (buffer, count) = export::export(logsdb.as_ref(), &filter, format, Vec::new()).await?;
+    if buffer.len() > MAX_ATTACHMENT_SIZE {
+        bail!(
+            "The export of {count} logs is {} MiB, over the Discord limit of {} MiB. Narrow the \
+             filters or run the `export` subcommand of the bot instead",
+            buffer.len().div_ceil(1024 * 1024),
+            MAX_ATTACHMENT_SIZE / 1024 / 1024
+        )
+    }
+
+    let filename = format!("{}.{}", group.group_id, format.extension());
+    let attachment = CreateAttachment::bytes(buffer, filename);
+    let builder = CreateReply::default()
+        .content(format!("Exported {count} logs"))
+        .attachment(attachment)
+        .ephemeral(true);
+
+    ctx.send(builder).await?;
+
+    Ok(())
+}
@@ -2,4 +2,12 @@ pub mod prelude;
 
 mod cheers;
 mod help;
+mod history;
+mod leaderboard;
+mod logs;
+mod paginate;
 mod pardon;
+mod purge;
+mod staff;
+mod sync;
+mod util;
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use color_eyre::{
+    eyre::{bail, Error},
+    Result,
+};
+use poise::{
+    serenity_prelude::{CreateInteractionResponse as CIR, *},
+    Context,
+    CreateReply,
+};
+
+use crate::{logsdb::Log, Data};
+
+/// Logs per page
+const PAGE_SIZE: usize = 10;
+
+/// # Send the embed pages with last and next buttons
+///
+/// # Errors
+/// Will return `Err` if there are no pages, `send` or `edit` fails.
+pub async fn paginate(ctx: Context<'_, Data, Error>, pages: Vec<CreateEmbed>) -> Result<()> {
+    if pages.is_empty() {
+        bail!("No results found")
+    }
+
+    let mut index = 0;
+    let reply = ctx.send(create_page(&pages, index)).await?;
+    let message_id = reply.message().await?.id;
+
+    while let Some(mci) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .message_id(message_id)
+        .timeout(Duration::MAX)
+        .await
+    {
+        mci.create_response(ctx, CIR::Acknowledge).await?;
+        match mci.data.custom_id.as_ref() {
+            "last" => index = index.saturating_sub(1),
+            "next" => index = (index + 1).min(pages.len() - 1),
+            _ => continue,
+        }
+
+        reply.edit(ctx, create_page(&pages, index)).await?;
+    }
+
+    Ok(())
+}
+
+/// # Create the pages of the logs (in order) from a template embed
+/// The footer counts the pages and the logs, called `noun`.
+#[must_use]
+pub fn create_log_pages(
+    logs: &[Log],
+    embed: &CreateEmbed,
+    noun: &str,
+    format: impl Fn(&Log) -> String,
+) -> Vec<CreateEmbed> {
+    let chunks = logs.chunks(PAGE_SIZE).collect::<Vec<_>>();
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let description = chunk
+                .iter()
+                .map(&format)
+                .collect::<Vec<_>>()
+                .join("\n\n");
+
+            let footer = format!(
+                "Page {}/{} - {} {noun}",
+                index + 1,
+                chunks.len(),
+                logs.len()
+            );
+
+            embed
+                .clone()
+                .description(description)
+                .footer(CreateEmbedFooter::new(footer))
+        })
+        .collect()
+}
+
+/// # Format a log with the staff member that did it
+#[must_use]
+pub fn format_log_by_actor(log: &Log) -> String {
+    let timestamp = log.created_at.unix_timestamp();
+    let actor_name = log.actor_display_name.as_deref().unwrap_or(&log.actor_id);
+    let actor_url = format!("https://vrchat.com/home/user/{}", log.actor_id);
+
+    format!(
+        "<t:{timestamp}:f> **{}** by [{actor_name}]({actor_url})\n{}",
+        log.event_type, log.description
+    )
+}
+
+/// # Format a log with the user it was done to
+#[must_use]
+pub fn format_log_on_target(log: &Log) -> String {
+    let timestamp = log.created_at.unix_timestamp();
+    let target = log.target_id.as_ref().map_or_else(String::new, |target_id| {
+        format!(" on [`{target_id}`](https://vrchat.com/home/user/{target_id})")
+    });
+
+    format!(
+        "<t:{timestamp}:f> **{}**{target}\n{}",
+        log.event_type, log.description
+    )
+}
+
+fn create_page(pages: &[CreateEmbed], index: usize) -> CreateReply {
+    /* Create and Add the last and next buttons */
+    let mut buttons = Vec::new();
+    if index > 0 {
+        let button = CreateButton::new("last")
+            .emoji('⬅')
+            .label("Last")
+            .style(ButtonStyle::Secondary);
+
+        buttons.push(button);
+    }
+    if index + 1 < pages.len() {
+        let button = CreateButton::new("next")
+            .emoji('➡')
+            .label("Next")
+            .style(ButtonStyle::Secondary);
+
+        buttons.push(button);
+    }
+
+    let components = if buttons.is_empty() {
+        Vec::new()
+    } else {
+        vec![CreateActionRow::Buttons(buttons)]
+    };
+
+    CreateReply::default()
+        .embed(pages[index].clone())
+        .components(components)
+}
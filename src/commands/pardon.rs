@@ -12,7 +12,11 @@ use poise::{
 };
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
-use crate::{logsdb::Log, Data};
+use super::util::find_group;
+use crate::{event::EventType, logsdb::Log, storage::Storage, users, Data};
+
+/// Logs fetched from the database per page
+const PAGE_SIZE: i64 = 10;
 
 struct Message<'a> {
     builder: CreateReply,
@@ -43,46 +47,67 @@ pub async fn pardon(
     #[description = "Search by User Name"] name: Option<String>,
     #[description = "Search by User UUID"] uuid: Option<String>,
 ) -> Result<()> {
-    let message = Message::new(ctx).await?;
     let Data {
         config: _,
         logsdb,
         vrchat,
+        sync: _,
     } = ctx.data();
 
+    /* Resolve the group before the placeholder so an error doesn't leave it behind */
+    let group = find_group(ctx)?;
+    let group_id = &group.group_id;
+    let message = Message::new(ctx).await?;
+
     /* Parse the moderator input (name, uuid, recent) */
-    let logs = if let Some(search) = name {
+    let target_id = if let Some(search) = name {
         let users = vrchat.search_users(&search).await?;
-        let Some(user) = users.first() else {
+        let Some(user) = users.into_iter().next() else {
+            message.reply.delete(ctx).await?;
             bail!("No user found")
         };
 
-        logsdb.get_recent_actions_by_id(&user.id).await?
-    } else if let Some(target_id) = uuid {
-        logsdb.get_recent_actions_by_id(&target_id).await?
+        Some(user.id)
     } else {
-        logsdb.get_all_recent_actions().await?
+        uuid
     };
 
     /* Paginate the unique user ids */
-    paginate_logs(ctx, message, &logs).await
+    paginate_logs(ctx, logsdb.as_ref(), message, group_id, target_id.as_deref()).await
 }
 
 async fn paginate_logs(
     ctx: Context<'_, Data, Error>,
+    logsdb: &dyn Storage,
     message: Message<'_>,
-    logs: &[Log],
+    group_id: &str,
+    target_id: Option<&str>,
 ) -> Result<()> {
     let mut index = 0;
+    let mut logs = Vec::new();
+    let mut exhausted = false;
 
     'done: loop {
+        /* Fetch the next page lazily once the moderator reaches the end */
+        if index >= logs.len() && !exhausted {
+            /* Fetch one extra log to know if there is a next page */
+            let mut page = logsdb
+                .get_latest_actions(group_id, target_id, logs.last(), PAGE_SIZE + 1)
+                .await?;
+
+            exhausted = page.len() <= usize::try_from(PAGE_SIZE)?;
+            page.truncate(usize::try_from(PAGE_SIZE)?);
+            logs.extend(page);
+        }
+
         let Some(log) = logs.get(index) else {
             message.reply.delete(ctx).await?;
             bail!("No results found")
         };
 
         let user_id = log.target_id.clone().ok_or_eyre("None")?;
-        edit_message_embed(ctx, &message, logs, index).await?;
+        let has_next = index + 1 < logs.len() || !exhausted;
+        edit_message_embed(ctx, &message, group_id, log, index, has_next).await?;
 
         /* Capture users button input in a loop until valid input is received */
         'page: while let Some(mci) = ComponentInteractionCollector::new(ctx)
@@ -92,9 +117,10 @@ async fn paginate_logs(
             .await
         {
             let Data {
-                config,
+                config: _,
                 logsdb: _,
                 vrchat,
+                sync: _,
             } = ctx.data();
 
             mci.create_response(ctx, CIR::Acknowledge).await?;
@@ -113,13 +139,13 @@ async fn paginate_logs(
                 }
                 "pardon" => {
                     message.reply.delete(ctx).await?;
-                    vrchat.pardon_member(&config.vrc_group_id, &user_id).await?;
+                    vrchat.pardon_member(group_id, &user_id).await?;
 
                     break 'done;
                 }
                 "ban" => {
                     message.reply.delete(ctx).await?;
-                    vrchat.ban_member(&config.vrc_group_id, &user_id).await?;
+                    vrchat.ban_member(group_id, &user_id).await?;
 
                     break 'done;
                 }
@@ -134,45 +160,35 @@ async fn paginate_logs(
 async fn edit_message_embed(
     ctx: Context<'_, Data, Error>,
     message: &Message<'_>,
-    logs: &[Log],
+    group_id: &str,
+    log: &Log,
     index: usize,
+    has_next: bool,
 ) -> Result<()> {
     let Data {
-        config,
-        logsdb: _,
+        config: _,
+        logsdb,
         vrchat,
+        sync: _,
     } = ctx.data();
 
-    /* Get the user and member */
-    let log = &logs[index];
+    /* Get the user from the cache first and the member */
     let user_id = log.target_id.clone().ok_or_eyre("None")?;
-    let user = vrchat.get_user(&user_id).await?;
-
-    /* Fallback to the avatar thumbnail without VRC+ */
-    let mut url = user.profile_pic_override_thumbnail;
-    if url.is_empty() {
-        url = user.current_avatar_thumbnail_image_url;
-    }
+    let user = users::get_user(logsdb.as_ref(), vrchat, &user_id).await?;
 
     /* Create the embed with information */
-    let author = CreateEmbedAuthor::new(user.display_name)
-        .icon_url(user.user_icon)
+    let author = CreateEmbedAuthor::new(&user.display_name)
+        .icon_url(&user.user_icon)
         .url(format!("https://vrchat.com/home/user/{user_id}"));
     let mut embed = CreateEmbed::default()
         .author(author)
-        .description(user.bio)
-        .image(url);
+        .description(&user.bio)
+        .image(&user.thumbnail);
 
     /* Add the badges (Supporter, Early Supporter) */
-    if let Some(badges) = user.badges {
-        let badges = badges
-            .into_iter()
-            .map(|badge| badge.badge_name)
-            .collect::<Vec<_>>();
-
-        if !badges.is_empty() {
-            embed = embed.field("Badges", badges.join(", "), true);
-        }
+    let badges = user.badge_names();
+    if !badges.is_empty() {
+        embed = embed.field("Badges", badges.join(", "), true);
     }
 
     /* Create and Add the last and next buttons */
@@ -185,7 +201,7 @@ async fn edit_message_embed(
 
         buttons.push(button);
     }
-    if index < logs.len() {
+    if has_next {
         let button = CreateButton::new("next")
             .emoji('➡')
             .label("Next")
@@ -194,19 +210,15 @@ async fn edit_message_embed(
         buttons.push(button);
     }
 
-    if let Ok(member) = vrchat
-        .get_group_member(&config.vrc_group_id, &user_id)
-        .await
-    {
-        let actor = vrchat.get_user(&log.actor_id).await?;
-        if let Some(text) = match log.event_type.as_ref() {
-            "group.user.ban" => Some(format!("Banned by {}", actor.display_name)),
-            "group.user.unban" => Some(String::from("Pardoned")),
+    if let Ok(member) = vrchat.get_group_member(group_id, &user_id).await {
+        let actor = users::get_user(logsdb.as_ref(), vrchat, &log.actor_id).await?;
+        if let Some(text) = match log.event_type {
+            EventType::Ban => Some(format!("Banned by {}", actor.display_name)),
+            EventType::Unban => Some(String::from("Pardoned")),
             _ => None,
         } {
             /* Add the staff member and when the action was done */
-            let date_time = OffsetDateTime::parse(&log.created_at, &Rfc3339)?;
-            let timestamp = Timestamp::from_unix_timestamp(date_time.unix_timestamp())?;
+            let timestamp = Timestamp::from_unix_timestamp(log.created_at.unix_timestamp())?;
             let footer = CreateEmbedFooter::new(text).icon_url(actor.user_icon);
             embed = embed.footer(footer).timestamp(timestamp);
         }
@@ -1 +1,11 @@
-pub use super::{cheers::*, help::*, pardon::*};
+pub use super::{
+    cheers::*,
+    help::*,
+    history::*,
+    leaderboard::*,
+    logs::*,
+    pardon::*,
+    purge::*,
+    staff::*,
+    sync::*,
+};
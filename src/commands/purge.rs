@@ -0,0 +1,34 @@
+use color_eyre::{eyre::Error, Result};
+use poise::{Context, CreateReply};
+
+use crate::{purge, Data};
+
+/// Purge the audit logs past their retention
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn purge(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Only show what would be purged (default: true)"] dry_run: Option<bool>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let Data {
+        config,
+        logsdb,
+        vrchat: _,
+        sync: _,
+    } = ctx.data();
+
+    let dry_run = dry_run.unwrap_or(true);
+    let content = if config.retention.is_empty() {
+        String::from("No retention rules configured, logs are kept forever")
+    } else {
+        purge::purge(logsdb.as_ref(), &config.retention, dry_run)
+            .await?
+            .to_string()
+    };
+
+    let builder = CreateReply::default().content(content).ephemeral(true);
+    ctx.send(builder).await?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use color_eyre::{
+    eyre::{bail, Error},
+    Result,
+};
+use poise::{serenity_prelude::*, Context};
+
+use super::{
+    paginate::{create_log_pages, format_log_on_target, paginate},
+    util::{autocomplete_event_type, find_group},
+};
+use crate::{date::{parse_date, parse_end_date}, event::EventType, users, Data};
+
+/// Review the staff members
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "BAN_MEMBERS",
+    subcommands("actions"),
+    subcommand_required
+)]
+pub async fn staff(_ctx: Context<'_, Data, Error>) -> Result<()> {
+    Ok(())
+}
+
+/// List the actions of a staff member
+#[poise::command(slash_command, guild_only, required_permissions = "BAN_MEMBERS")]
+async fn actions(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Search by Staff Name"] name: Option<String>,
+    #[description = "Search by Staff UUID"] uuid: Option<String>,
+    #[description = "Only this event type"]
+    #[autocomplete = "autocomplete_event_type"]
+    event_type: Option<String>,
+    #[description = "Start date (YYYY-MM-DD or RFC 3339)"] start: Option<String>,
+    #[description = "End date (YYYY-MM-DD includes the day, or RFC 3339)"] end: Option<String>,
+) -> Result<()> {
+    ctx.defer().await?;
+
+    let Data {
+        config: _,
+        logsdb,
+        vrchat,
+        sync: _,
+    } = ctx.data();
+
+    let group = find_group(ctx)?;
+
+    /* Parse the moderator input (name, uuid, filters) */
+    let (actor_id, display_name) = if let Some(search) = name {
+        let users = vrchat.search_users(&search).await?;
+        let Some(user) = users.into_iter().next() else {
+            bail!("No user found")
+        };
+
+        (user.id, user.display_name)
+    } else if let Some(actor_id) = uuid {
+        let user = users::get_user(logsdb.as_ref(), vrchat, &actor_id).await?;
+
+        (user.id, user.display_name)
+    } else {
+        bail!("Search by Staff Name or UUID")
+    };
+
+    let event_type = event_type.as_deref().map(EventType::from);
+    let start = start.as_deref().map(parse_date).transpose()?;
+    let end = end.as_deref().map(parse_end_date).transpose()?;
+    let logs = logsdb
+        .get_actions_by_actor(&group.group_id, &actor_id, event_type.as_ref(), start, end)
+        .await?;
+
+    if logs.is_empty() {
+        bail!("No actions found for {display_name}")
+    }
+
+    /* Paginate the actions (newest first) */
+    let author = CreateEmbedAuthor::new(display_name)
+        .url(format!("https://vrchat.com/home/user/{actor_id}"));
+    let embed = CreateEmbed::default().author(author).title("Staff Actions");
+    let pages = create_log_pages(&logs, &embed, "actions", format_log_on_target);
+
+    paginate(ctx, pages).await
+}
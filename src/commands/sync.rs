@@ -0,0 +1,143 @@
+use color_eyre::{eyre::Error, Result};
+use poise::{serenity_prelude::*, Context, CreateReply};
+
+use super::util::find_group;
+use crate::{
+    date::{parse_date, parse_end_date},
+    sync::{self, SyncMode},
+    Data,
+};
+
+/// Manage the audit log sync
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("status", "now", "backfill", "reprocess"),
+    subcommand_required
+)]
+pub async fn sync(_ctx: Context<'_, Data, Error>) -> Result<()> {
+    Ok(())
+}
+
+/// Show the audit log sync status
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+async fn status(ctx: Context<'_, Data, Error>) -> Result<()> {
+    let Data {
+        config: _,
+        logsdb,
+        vrchat: _,
+        sync,
+    } = ctx.data();
+
+    let status = sync.status().await;
+    let quarantined = logsdb.count_quarantined().await?;
+
+    let last_sync = status.last_sync.map_or_else(
+        || String::from("Never"),
+        |date_time| format!("<t:{}:R>", date_time.unix_timestamp()),
+    );
+    let backoff = status.backoff.map_or_else(
+        || String::from("None"),
+        |backoff| format!("{}s", backoff.as_secs()),
+    );
+    let last_error = status.last_error.unwrap_or_else(|| String::from("None"));
+    let state = if status.degraded {
+        "Degraded"
+    } else {
+        "Healthy"
+    };
+
+    let embed = CreateEmbed::default()
+        .title("Audit Log Sync")
+        .field("State", state, true)
+        .field("Last Sync", last_sync, true)
+        .field("Failures", status.failures.to_string(), true)
+        .field("Backoff", backoff, true)
+        .field("Last Run", status.last_stats.to_string(), true)
+        .field("Since Startup", status.total.to_string(), true)
+        .field("Restarts", status.restarts.to_string(), true)
+        .field("Quarantined", quarantined.to_string(), true)
+        .field("Last Error", last_error, false)
+        .timestamp(Timestamp::now());
+
+    let builder = CreateReply::default().embed(embed).ephemeral(true);
+    ctx.send(builder).await?;
+
+    Ok(())
+}
+
+/// Sync the newest audit logs now
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+async fn now(ctx: Context<'_, Data, Error>) -> Result<()> {
+    run_in_background(ctx, SyncMode::Incremental, "Synced", "sync").await
+}
+
+/// Backfill the audit log history (optionally between two dates)
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+async fn backfill(
+    ctx: Context<'_, Data, Error>,
+    #[description = "Start date (YYYY-MM-DD or RFC 3339)"] start: Option<String>,
+    #[description = "End date (YYYY-MM-DD includes the day, or RFC 3339)"] end: Option<String>,
+) -> Result<()> {
+    let start = start.as_deref().map(parse_date).transpose()?;
+    let end = end.as_deref().map(parse_end_date).transpose()?;
+    let mode = SyncMode::Backfill { start, end };
+
+    run_in_background(ctx, mode, "Backfilled", "backfill").await
+}
+
+/// # Run a sync of the group in the background and post the result in the channel
+/// A backfill can outlast the interaction token, so the reply only says it was queued.
+///
+/// # Errors
+/// Will return `Err` if `find_group` or `send` fails.
+async fn run_in_background(
+    ctx: Context<'_, Data, Error>,
+    mode: SyncMode,
+    done: &'static str,
+    action: &'static str,
+) -> Result<()> {
+    let Data {
+        config: _,
+        logsdb: _,
+        vrchat: _,
+        sync,
+    } = ctx.data();
+
+    let group_id = find_group(ctx)?.group_id.clone();
+    let http = ctx.serenity_context().http.clone();
+    let channel_id = ctx.channel_id();
+    let sync = sync.clone();
+
+    tokio::spawn(async move {
+        let content = match sync.run(mode, Some(&group_id)).await {
+            Ok(stats) => format!("{done} {group_id} audit logs: {stats}"),
+            Err(error) => format!("Failed to {action} {group_id} audit logs: {error}"),
+        };
+
+        if let Err(error) = channel_id.say(http.as_ref(), content).await {
+            eprintln!("Error: Failed to post the {action} result: {error}");
+        }
+    });
+
+    let content = format!("Queued the audit log {action}, the result will be posted here");
+    let builder = CreateReply::default().content(content).ephemeral(true);
+    ctx.send(builder).await?;
+
+    Ok(())
+}
+
+/// Re-process the quarantined audit log entries
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+async fn reprocess(ctx: Context<'_, Data, Error>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let stats = sync::reprocess(ctx.data().logsdb.as_ref()).await?;
+    let content = format!("Re-processed quarantined audit logs: {stats}");
+    let builder = CreateReply::default().content(content).ephemeral(true);
+    ctx.send(builder).await?;
+
+    Ok(())
+}
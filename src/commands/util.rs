@@ -0,0 +1,31 @@
+use color_eyre::{
+    eyre::{Error, OptionExt},
+    Result,
+};
+use poise::Context;
+
+use crate::{config::Group, event::EventType, Data};
+
+/// # Find the `VRChat` group of the guild and channel the command was used in
+///
+/// # Errors
+/// Will return `Err` if no group is mapped to the guild and channel.
+pub fn find_group(ctx: Context<'_, Data, Error>) -> Result<&Group> {
+    ctx.data()
+        .config
+        .find_group(ctx.guild_id(), ctx.channel_id())
+        .ok_or_eyre("No VRChat group for this server")
+}
+
+/// Suggest the known event types matching the input
+#[allow(clippy::unused_async)]
+pub async fn autocomplete_event_type<'a>(
+    _ctx: Context<'_, Data, Error>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    EventType::KNOWN
+        .iter()
+        .map(EventType::as_str)
+        .filter(move |name| name.contains(partial))
+        .map(ToOwned::to_owned)
+}
@@ -1,5 +1,11 @@
 use derive_config::DeriveTomlConfig;
-use serde::{Deserialize, Serialize};
+use poise::serenity_prelude::{ChannelId, GuildId};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+use crate::event::EventType;
+
+/// Shortest sync interval (seconds), the pipeline is there for real-time events
+const MIN_SYNC_INTERVAL: u64 = 60;
 
 #[derive(Clone, Debug, DeriveTomlConfig, Deserialize, Serialize)]
 pub struct Config {
@@ -9,12 +15,125 @@ pub struct Config {
     pub bot_secret:   String,
     pub sql_secret:   String,
     pub vrc_secret:   String,
-    pub vrc_group_id: String,
     pub vrc_password: String,
     pub vrc_username: String,
 
+    /// Deprecated single group, migrated into `vrc_groups` by `Config::migrate`
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub vrc_group_id: Option<String>,
+    #[serde(default)]
+    pub vrc_groups:   Vec<Group>,
+
     #[serde(default)]
     pub vrc_cookies: Vec<String>,
+
+    #[serde(default)]
+    pub backfill_on_startup: bool,
+
+    #[serde(default = "default_sync_interval")]
+    #[serde(deserialize_with = "deserialize_sync_interval")]
+    pub sync_interval:     u64,
+    #[serde(default = "default_sync_max_backoff")]
+    pub sync_max_backoff:  u64,
+    #[serde(default = "default_sync_max_failures")]
+    pub sync_max_failures: u32,
+
+    #[serde(default)]
+    pub pipeline_enabled: bool,
+    #[serde(default = "default_pipeline_url")]
+    pub pipeline_url:     String,
+
+    #[serde(default = "default_leaderboard_recent_days")]
+    pub leaderboard_recent_days: i64,
+
+    #[serde(default)]
+    pub retention:      Vec<Retention>,
+    #[serde(default = "default_purge_interval")]
+    #[serde(deserialize_with = "deserialize_interval")]
+    pub purge_interval: u64,
+    #[serde(default)]
+    pub purge_dry_run:  bool,
+
+    #[serde(default = "default_user_cache_ttl")]
+    pub user_cache_ttl:        u64,
+    #[serde(default = "default_user_refresh_interval")]
+    #[serde(deserialize_with = "deserialize_interval")]
+    pub user_refresh_interval: u64,
+    #[serde(default = "default_user_refresh_batch")]
+    pub user_refresh_batch:    u32,
+}
+
+/// `VRChat` group mapped to a Discord guild (and optionally specific channels)
+/// Groups without a guild (migrated from `vrc_group_id`) belong to every guild.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Group {
+    pub group_id: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<GuildId>,
+
+    #[serde(default)]
+    pub channel_ids: Vec<ChannelId>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_channel_id:  Option<ChannelId>,
+    #[serde(default)]
+    pub log_event_types: Vec<EventType>,
+}
+
+/// Retention rule, logs of the event type are purged after the days
+/// Event types without a rule are kept forever.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Retention {
+    pub event_type: EventType,
+    #[serde(deserialize_with = "deserialize_retention_days")]
+    pub days:       i64,
+}
+
+impl Config {
+    /// # Migrate the deprecated `vrc_group_id` into a group without a guild
+    /// Returns `true` if the config changed and should be saved.
+    pub fn migrate(&mut self) -> bool {
+        let Some(group_id) = self.vrc_group_id.take() else {
+            return false;
+        };
+
+        if !self.vrc_groups.iter().any(|g| g.group_id == group_id) {
+            self.vrc_groups.push(Group {
+                group_id,
+                guild_id: None,
+                channel_ids: Vec::new(),
+                log_channel_id: None,
+                log_event_types: Vec::new(),
+            });
+        }
+
+        true
+    }
+
+    /// # Find the group that belongs to a Discord guild and channel
+    /// Groups mapped to the channel are preferred over groups mapped to the guild,
+    /// which are preferred over groups without a guild.
+    #[must_use]
+    pub fn find_group(&self, guild_id: Option<GuildId>, channel_id: ChannelId) -> Option<&Group> {
+        let guild_id = guild_id?;
+        let mut groups = self
+            .vrc_groups
+            .iter()
+            .filter(|g| g.guild_id.is_none_or(|id| id == guild_id));
+
+        groups
+            .clone()
+            .find(|g| g.channel_ids.contains(&channel_id))
+            .or_else(|| {
+                groups
+                    .clone()
+                    .find(|g| g.guild_id.is_some() && g.channel_ids.is_empty())
+            })
+            .or_else(|| groups.find(|g| g.channel_ids.is_empty()))
+    }
 }
 
 /// Get the default user agent
@@ -27,6 +146,85 @@ fn default_user_agent() -> String {
     )
 }
 
+/// Get the default sync interval (seconds)
+const fn default_sync_interval() -> u64 {
+    600
+}
+
+/// Get the default maximum sync backoff (seconds)
+const fn default_sync_max_backoff() -> u64 {
+    3600
+}
+
+/// Get the default maximum consecutive sync failures
+const fn default_sync_max_failures() -> u32 {
+    10
+}
+
+/// Get the default `VRChat` pipeline websocket url
+fn default_pipeline_url() -> String {
+    String::from("wss://pipeline.vrchat.cloud/")
+}
+
+/// Get the default leaderboard recent window (days)
+const fn default_leaderboard_recent_days() -> i64 {
+    30
+}
+
+/// Get the default purge interval (seconds)
+const fn default_purge_interval() -> u64 {
+    86400
+}
+
+/// Get the default user profile cache TTL (seconds)
+const fn default_user_cache_ttl() -> u64 {
+    86400
+}
+
+/// Get the default user profile refresh interval (seconds)
+const fn default_user_refresh_interval() -> u64 {
+    3600
+}
+
+/// Get the default users refreshed per interval, to spare the API quota
+const fn default_user_refresh_batch() -> u32 {
+    50
+}
+
+/// Deserialize the retention days, zero or less would purge every log of the event type
+fn deserialize_retention_days<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    let days = i64::deserialize(deserializer)?;
+    if days <= 0 {
+        return Err(D::Error::custom(format!(
+            "retention days must be positive, got {days}"
+        )));
+    }
+
+    Ok(days)
+}
+
+/// Deserialize the sync interval, anything shorter would poll `VRChat` in a tight loop
+fn deserialize_sync_interval<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let interval = u64::deserialize(deserializer)?;
+    if interval < MIN_SYNC_INTERVAL {
+        return Err(D::Error::custom(format!(
+            "sync interval must be at least {MIN_SYNC_INTERVAL} seconds, got {interval}"
+        )));
+    }
+
+    Ok(interval)
+}
+
+/// Deserialize an interval (seconds), `tokio::time::interval` panics on zero
+fn deserialize_interval<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let interval = u64::deserialize(deserializer)?;
+    if interval == 0 {
+        return Err(D::Error::custom("interval must be at least 1 second, got 0"));
+    }
+
+    Ok(interval)
+}
+
 /// Check if the user agent is default
 fn is_default(user_agent: &str) -> bool {
     user_agent.starts_with(env!("CARGO_PKG_NAME"))
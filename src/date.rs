@@ -0,0 +1,38 @@
+use color_eyre::{eyre::OptionExt, Result};
+use time::{
+    format_description::well_known::Rfc3339,
+    macros::format_description,
+    Date,
+    OffsetDateTime,
+};
+
+/// # Parse a date (YYYY-MM-DD) or date time (RFC 3339)
+/// Dates are midnight UTC.
+///
+/// # Errors
+/// Will return `Err` if the input is neither format.
+pub fn parse_date(input: &str) -> Result<OffsetDateTime> {
+    if let Ok(date_time) = OffsetDateTime::parse(input, &Rfc3339) {
+        return Ok(date_time);
+    }
+
+    let date = Date::parse(input, format_description!("[year]-[month]-[day]"))?;
+
+    Ok(date.midnight().assume_utc())
+}
+
+/// # Parse the exclusive end of a range, a date (YYYY-MM-DD) or date time (RFC 3339)
+/// Dates are the next midnight UTC, so the whole day is in the range.
+///
+/// # Errors
+/// Will return `Err` if the input is neither format or the date is the last one.
+pub fn parse_end_date(input: &str) -> Result<OffsetDateTime> {
+    if let Ok(date_time) = OffsetDateTime::parse(input, &Rfc3339) {
+        return Ok(date_time);
+    }
+
+    let date = Date::parse(input, format_description!("[year]-[month]-[day]"))?;
+    let date = date.next_day().ok_or_eyre("End date is out of range")?;
+
+    Ok(date.midnight().assume_utc())
+}
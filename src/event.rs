@@ -0,0 +1,113 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Define the `EventType` variants along with their `VRChat` event type strings
+macro_rules! event_types {
+    ($($(#[$meta:meta])* $variant:ident => $name:literal,)+) => {
+        /// `VRChat` group audit log event type
+        #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum EventType {
+            $($(#[$meta])* $variant,)+
+            /// Event type this bot doesn't know about yet
+            Unknown(String),
+        }
+
+        impl EventType {
+            /// Every known event type
+            pub const KNOWN: &'static [Self] = &[$(Self::$variant),+];
+
+            /// # Get the `VRChat` event type string
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $name,)+
+                    Self::Unknown(name) => name,
+                }
+            }
+        }
+
+        impl From<&str> for EventType {
+            fn from(name: &str) -> Self {
+                match name {
+                    $($name => Self::$variant,)+
+                    _ => Self::Unknown(name.to_owned()),
+                }
+            }
+        }
+    };
+}
+
+event_types! {
+    /// Group settings were updated
+    Update => "group.update",
+    /// User joined the group
+    Join => "group.user.join",
+    /// User left the group
+    Leave => "group.user.leave",
+    /// Member was removed from the group
+    Kick => "group.member.remove",
+    /// User was banned from the group
+    Ban => "group.user.ban",
+    /// User was unbanned from the group
+    Unban => "group.user.unban",
+    /// Role was assigned to a member
+    RoleAssign => "group.member.role.assign",
+    /// Role was unassigned from a member
+    RoleUnassign => "group.member.role.unassign",
+    /// Role was created
+    RoleCreate => "group.role.create",
+    /// Role was updated
+    RoleUpdate => "group.role.update",
+    /// Role was deleted
+    RoleDelete => "group.role.delete",
+    /// Group instance was opened
+    InstanceCreate => "group.instance.create",
+    /// Group instance was closed
+    InstanceClose => "group.instance.close",
+    /// User was kicked from a group instance
+    InstanceKick => "group.instance.kick",
+    /// User was warned in a group instance
+    InstanceWarn => "group.instance.warn",
+    /// User was invited to the group
+    InviteCreate => "group.invite.create",
+    /// Group invite was cancelled
+    InviteCancel => "group.invite.cancel",
+    /// User requested to join the group
+    RequestCreate => "group.request.create",
+    /// Join request was rejected
+    RequestReject => "group.request.reject",
+    /// Group post was created
+    PostCreate => "group.post.create",
+    /// Group post was deleted
+    PostDelete => "group.post.delete",
+    /// Group announcement was created
+    AnnouncementCreate => "group.announcement.create",
+    /// Group announcement was deleted
+    AnnouncementDelete => "group.announcement.delete",
+}
+
+impl From<String> for EventType {
+    fn from(name: String) -> Self {
+        match Self::from(name.as_str()) {
+            Self::Unknown(_) => Self::Unknown(name),
+            event_type => event_type,
+        }
+    }
+}
+
+impl From<EventType> for String {
+    fn from(event_type: EventType) -> Self {
+        match event_type {
+            EventType::Unknown(name) => name,
+            event_type => event_type.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
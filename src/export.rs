@@ -0,0 +1,204 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use color_eyre::{
+    eyre::{bail, OptionExt},
+    Result,
+};
+use poise::ChoiceParameter;
+use time::format_description::well_known::Rfc3339;
+
+use crate::{
+    date::{parse_date, parse_end_date},
+    event::EventType,
+    logsdb::Log,
+    storage::{LogFilter, Storage},
+};
+
+/// CSV columns, in the same order as `Log`
+const CSV_HEADER: &str =
+    "id,created_at,group_id,actor_id,actor_display_name,target_id,event_type,description,data";
+
+/// Export file format
+#[derive(ChoiceParameter, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ExportFormat {
+    #[default]
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+    #[name = "NDJSON"]
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// # Get the file extension of the format
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Writes the logs one at a time in the export format
+pub struct Exporter<W: Write> {
+    format: ExportFormat,
+    writer: W,
+    count:  u64,
+}
+
+impl<W: Write> Exporter<W> {
+    /// # Start the export with the header (CSV) or opening bracket (JSON)
+    ///
+    /// # Errors
+    /// Will return `Err` if the writer fails.
+    pub fn new(format: ExportFormat, mut writer: W) -> Result<Self> {
+        match format {
+            ExportFormat::Csv => writeln!(writer, "{CSV_HEADER}")?,
+            ExportFormat::Json => write!(writer, "[")?,
+            ExportFormat::Ndjson => {}
+        }
+
+        Ok(Self {
+            format,
+            writer,
+            count: 0,
+        })
+    }
+
+    /// # Write a log
+    ///
+    /// # Errors
+    /// Will return `Err` if the log fails to serialize or the writer fails.
+    pub fn write(&mut self, log: &Log) -> Result<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                let fields = [
+                    log.id.clone(),
+                    log.created_at.format(&Rfc3339)?,
+                    log.group_id.clone(),
+                    log.actor_id.clone(),
+                    log.actor_display_name.clone().unwrap_or_default(),
+                    log.target_id.clone().unwrap_or_default(),
+                    log.event_type.to_string(),
+                    log.description.clone(),
+                    log.data.to_string(),
+                ];
+
+                let row = fields
+                    .iter()
+                    .map(|field| escape_csv(field))
+                    .collect::<Vec<_>>();
+                writeln!(self.writer, "{}", row.join(","))?;
+            }
+            ExportFormat::Json => {
+                /* Indent the pretty JSON object inside the array */
+                let separator = if self.count == 0 { "\n" } else { ",\n" };
+                let json = serde_json::to_string_pretty(log)?.replace('\n', "\n  ");
+                write!(self.writer, "{separator}  {json}")?;
+            }
+            ExportFormat::Ndjson => {
+                serde_json::to_writer(&mut self.writer, log)?;
+                writeln!(self.writer)?;
+            }
+        }
+
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// # Finish the export with the closing bracket (JSON) and flush
+    ///
+    /// # Errors
+    /// Will return `Err` if the writer fails.
+    pub fn finish(mut self) -> Result<W> {
+        if self.format == ExportFormat::Json {
+            let separator = if self.count == 0 { "" } else { "\n" };
+            writeln!(self.writer, "{separator}]")?;
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// # Quote a CSV field if it contains a delimiter, quote or newline
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// # Export the logs matching the filter (oldest first)
+/// Returns the writer and the number of logs exported.
+///
+/// # Errors
+/// Will return `Err` if `for_each_log` or the `Exporter` fails.
+pub async fn export<W: Write + Send>(
+    logsdb: &dyn Storage,
+    filter: &LogFilter,
+    format: ExportFormat,
+    writer: W,
+) -> Result<(W, u64)> {
+    let mut exporter = Exporter::new(format, writer)?;
+    let count = logsdb
+        .for_each_log(filter, &mut |log| exporter.write(&log))
+        .await?;
+
+    Ok((exporter.finish()?, count))
+}
+
+/// # Run the export CLI subcommand
+/// `export [--format csv|json|ndjson] [--group ID] [--event-type TYPE] [--actor ID]
+/// [--target ID] [--start DATE] [--end DATE] [--output FILE]`
+///
+/// # Errors
+/// Will return `Err` if an argument is invalid or `export` fails.
+pub async fn cli(logsdb: &dyn Storage, args: Vec<String>) -> Result<()> {
+    let mut args = args.into_iter();
+    let mut filter = LogFilter::default();
+    let mut format = ExportFormat::default();
+    let mut output = None;
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_eyre(format!("Missing value for {flag}"))?;
+        match flag.as_str() {
+            "--format" => {
+                format = ExportFormat::from_name(&value)
+                    .ok_or_eyre(format!("Unknown export format: {value}"))?;
+            }
+            "--group" => filter.group_id = Some(value),
+            "--event-type" => filter.event_type = Some(EventType::from(value)),
+            "--actor" => filter.actor_id = Some(value),
+            "--target" => filter.target_id = Some(value),
+            "--start" => filter.start = Some(parse_date(&value)?),
+            "--end" => filter.end = Some(parse_end_date(&value)?),
+            "--output" => output = Some(value),
+            _ => bail!("Unknown export argument: {flag}"),
+        }
+    }
+
+    let count = if let Some(path) = output {
+        let writer = BufWriter::new(File::create(path)?);
+        export(logsdb, &filter, format, writer).await?.1
+    } else {
+        let writer = BufWriter::new(io::stdout());
+        export(logsdb, &filter, format, writer).await?.1
+    };
+
+    /* Report on stderr so stdout only holds the export */
+    eprintln!("Exported {count} logs");
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use color_eyre::Result;
+use poise::serenity_prelude::*;
+
+use crate::{event::EventType, logsdb::Log};
+
+/// Logs posted per sync, older logs are summarised to respect the Discord rate limits
+const MAX_POSTS: usize = 20;
+
+/// Discord channel feed of newly synced audit logs
+pub struct Feed {
+    pub channel_id:  ChannelId,
+    pub event_types: Vec<EventType>,
+}
+
+impl Feed {
+    #[must_use]
+    pub const fn new(channel_id: ChannelId, event_types: Vec<EventType>) -> Self {
+        Self {
+            channel_id,
+            event_types,
+        }
+    }
+
+    /// # Check if the log event type should be posted
+    #[must_use]
+    pub fn accepts(&self, log: &Log) -> bool {
+        self.event_types.is_empty() || self.event_types.contains(&log.event_type)
+    }
+
+    /// # Post the logs to the feed channel (oldest first)
+    /// Only the newest `MAX_POSTS` logs are posted, the skipped logs are counted.
+    ///
+    /// # Errors
+    /// Will return `Err` if `send_message` fails.
+    pub async fn post(&self, http: &Http, logs: &[Log]) -> Result<()> {
+        let logs = logs
+            .iter()
+            .filter(|log| self.accepts(log))
+            .collect::<Vec<_>>();
+
+        let skipped = logs.len().saturating_sub(MAX_POSTS);
+        if skipped > 0 {
+            let content = format!("Skipped {skipped} older audit logs, see `/logs export`");
+            let builder = CreateMessage::default().content(content);
+            self.channel_id.send_message(http, builder).await?;
+        }
+
+        for log in logs.into_iter().take(MAX_POSTS).rev() {
+            let builder = CreateMessage::default().embed(create_log_embed(log));
+            self.channel_id.send_message(http, builder).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// # Create an embed for an audit log
+pub fn create_log_embed(log: &Log) -> CreateEmbed {
+    let actor_name = log.actor_display_name.as_deref().unwrap_or(&log.actor_id);
+    let actor_url = format!("https://vrchat.com/home/user/{}", log.actor_id);
+    let author = CreateEmbedAuthor::new(actor_name).url(actor_url);
+
+    let mut embed = CreateEmbed::default()
+        .author(author)
+        .title(log.event_type.as_str())
+        .description(&log.description)
+        .field("Actor", format!("`{}`", log.actor_id), true)
+        .footer(CreateEmbedFooter::new(&log.id));
+
+    if let Some(target_id) = &log.target_id {
+        let target_url = format!("https://vrchat.com/home/user/{target_id}");
+        let target = format!("[`{target_id}`]({target_url})");
+        embed = embed.url(target_url).field("Target", target, true);
+    }
+
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp(log.created_at.unix_timestamp()) {
+        embed = embed.timestamp(timestamp);
+    }
+
+    embed
+}
@@ -0,0 +1,156 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
+
+use color_eyre::{eyre::bail, Report, Result};
+use serde_json::Value;
+
+use crate::{logsdb::Log, storage::Storage};
+
+/// Logs between progress reports
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// Maximum length of the id columns
+const MAX_ID_LEN: usize = 64;
+
+/// Audit log import statistics
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ImportStats {
+    pub imported:   usize,
+    pub duplicates: usize,
+    pub invalid:    usize,
+}
+
+impl ImportStats {
+    /// # Get the total number of logs read
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.imported + self.duplicates + self.invalid
+    }
+}
+
+impl fmt::Display for ImportStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} imported, {} duplicates, {} invalid",
+            self.imported, self.duplicates, self.invalid
+        )
+    }
+}
+
+/// # Import the logs from an export (JSON array or NDJSON)
+/// The format is detected by the first character, duplicates and invalid logs are skipped.
+///
+/// # Errors
+/// Will return `Err` if reading, parsing the JSON array or `insert_log` fails.
+pub async fn import<R: BufRead + Send>(logsdb: &dyn Storage, mut reader: R) -> Result<ImportStats> {
+    let mut stats = ImportStats::default();
+    if starts_with_array(&mut reader)? {
+        let entries: Vec<Value> = serde_json::from_reader(reader)?;
+        for (index, entry) in entries.into_iter().enumerate() {
+            let log = serde_json::from_value(entry).map_err(Report::from);
+            import_log(logsdb, index + 1, log, &mut stats).await?;
+        }
+    } else {
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let log = serde_json::from_str(&line).map_err(Report::from);
+            import_log(logsdb, index + 1, log, &mut stats).await?;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// # Check if the first non-whitespace character opens a JSON array
+fn starts_with_array<R: BufRead>(reader: &mut R) -> Result<bool> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+
+        if let Some(byte) = buffer.iter().find(|byte| !byte.is_ascii_whitespace()) {
+            return Ok(*byte == b'[');
+        }
+
+        let len = buffer.len();
+        reader.consume(len);
+    }
+}
+
+/// # Validate and insert a log, then update the statistics
+async fn import_log(
+    logsdb: &dyn Storage,
+    number: usize,
+    log: Result<Log>,
+    stats: &mut ImportStats,
+) -> Result<()> {
+    match log.and_then(|log| validate(&log).map(|()| log)) {
+        Ok(log) => {
+            if logsdb.insert_log(&log).await? {
+                stats.imported += 1;
+            } else {
+                stats.duplicates += 1;
+            }
+        }
+        Err(error) => {
+            eprintln!("Error: Invalid log #{number}: {error}");
+            stats.invalid += 1;
+        }
+    }
+
+    if stats.total().is_multiple_of(PROGRESS_INTERVAL) {
+        eprintln!("Importing audit logs: {stats}");
+    }
+
+    Ok(())
+}
+
+/// # Validate the fields the database constrains
+fn validate(log: &Log) -> Result<()> {
+    let ids = [
+        ("id", Some(&log.id)),
+        ("group_id", Some(&log.group_id)),
+        ("actor_id", Some(&log.actor_id)),
+        ("target_id", log.target_id.as_ref()),
+    ];
+
+    for (name, id) in ids {
+        match id {
+            Some(id) if id.trim().is_empty() => bail!("{name} is empty"),
+            Some(id) if id.len() > MAX_ID_LEN => bail!("{name} is longer than {MAX_ID_LEN}"),
+            _ => {}
+        }
+    }
+
+    if log.event_type.as_str().is_empty() {
+        bail!("event_type is empty");
+    }
+
+    Ok(())
+}
+
+/// # Run the import CLI subcommand
+/// `import [FILE]`, reads stdin without a file.
+///
+/// # Errors
+/// Will return `Err` if the file can't be opened or `import` fails.
+pub async fn cli(logsdb: &dyn Storage, args: Vec<String>) -> Result<()> {
+    let stats = match args.as_slice() {
+        [] => import(logsdb, BufReader::new(io::stdin())).await?,
+        [path] => import(logsdb, BufReader::new(File::open(path)?)).await?,
+        _ => bail!("Usage: import [FILE]"),
+    };
+
+    println!("Imported audit logs: {stats}");
+
+    Ok(())
+}
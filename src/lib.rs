@@ -1,29 +1,48 @@
-use std::time::Duration;
+use std::sync::Arc;
 
 use color_eyre::{eyre::Error, Result};
 use config::Config;
-use logsdb::LogsDB;
-use poise::{serenity_prelude::*, Framework, FrameworkContext};
+use poise::{serenity_prelude::*, Framework};
+use storage::Storage;
+use sync::SyncHandle;
 use vrchat::VRChat;
 
 pub mod commands;
 pub mod config;
+pub mod date;
+pub mod event;
+pub mod export;
+pub mod feed;
+pub mod import;
 pub mod logsdb;
+pub mod memorydb;
+pub mod pipeline;
+pub mod purge;
+pub mod storage;
+pub mod sync;
+pub mod users;
 pub mod vrchat;
 
 pub struct Data {
     pub config: config::Config,
-    pub logsdb: logsdb::LogsDB,
-    pub vrchat: vrchat::VRChat,
+    pub logsdb: Arc<dyn storage::Storage>,
+    pub vrchat: Arc<vrchat::VRChat>,
+    pub sync:   sync::SyncHandle,
 }
 
 impl Data {
     #[must_use]
-    pub const fn new(config: Config, logsdb: LogsDB, vrchat: VRChat) -> Self {
+    pub const fn new(
+        config: Config,
+        logsdb: Arc<dyn Storage>,
+        vrchat: Arc<VRChat>,
+        sync: SyncHandle,
+    ) -> Self {
         Self {
             config,
             logsdb,
             vrchat,
+            sync,
         }
     }
 
@@ -49,43 +68,4 @@ impl Data {
 
         Ok(self)
     }
-
-    /// # Handle Events
-    ///
-    /// # Errors
-    /// Will not return `Err`
-    pub async fn event_handler(
-        &self,
-        _ctx: &Context,
-        event: &FullEvent,
-        _framework: FrameworkContext<'_, Self, Error>,
-    ) -> Result<()> {
-        let Self {
-            config,
-            logsdb,
-            vrchat,
-        } = self;
-
-        let FullEvent::Ready { data_about_bot: _ } = event else {
-            return Ok(());
-        };
-
-        loop {
-            let Ok(logs) = vrchat
-                .get_group_audit_logs(&config.vrc_group_id, 100, 0)
-                .await
-            else {
-                continue;
-            };
-
-            for log in logs {
-                if let Err(error) = logsdb.insert_log(log).await {
-                    eprintln!("Error: {error}");
-                    break;
-                };
-            }
-
-            tokio::time::sleep(Duration::from_secs(600)).await;
-        }
-    }
 }
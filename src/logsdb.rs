@@ -1,35 +1,213 @@
-use color_eyre::{eyre::OptionExt, Report, Result};
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{bail, OptionExt},
+    Report,
+    Result,
+};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::{mysql::MySqlQueryResult, prelude::*, MySqlPool};
-use vrchatapi::models::GroupAuditLogEntry;
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    prelude::*,
+    Database,
+    MySql,
+    MySqlPool,
+    QueryBuilder,
+};
+#[cfg(feature = "sqlite")]
+use sqlx::{
+    sqlite::{SqliteArgumentValue, SqliteConnectOptions, SqliteTypeInfo},
+    Sqlite,
+    SqlitePool,
+};
+#[cfg(feature = "sqlite")]
+use time::{macros::format_description, UtcOffset};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use vrchatapi::models::{GroupAuditLogEntry, User};
 
-/// Wrapper around `sqlx::MySqlPool`
-pub struct LogsDB(pub MySqlPool);
+use crate::{
+    event::EventType,
+    storage::{LogFilter, Storage},
+};
+
+/// Wrapper around the `sqlx` pool of the backend selected by the url scheme
+pub enum LogsDB {
+    MySql(MySqlPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqlitePool),
+    #[cfg(feature = "postgres")]
+    Postgres(PgPool),
+}
+
+/// Run the same `sqlx` expression against whichever backend is connected
+macro_rules! with_pool {
+    ($logsdb:expr, |$pool:ident| $body:expr) => {
+        match $logsdb {
+            LogsDB::MySql($pool) => $body,
+            #[cfg(feature = "sqlite")]
+            LogsDB::Sqlite($pool) => $body,
+            #[cfg(feature = "postgres")]
+            LogsDB::Postgres($pool) => $body,
+        }
+    };
+}
+
+/// `OffsetDateTime` bind parameter, fixed-width UTC text on `SQLite` so it sorts chronologically
+/// `sqlx` would encode a varying number of fraction digits, and `.` sorts before `Z`.
+#[derive(Clone, Copy, Debug)]
+struct DateTime(OffsetDateTime);
+
+/// Bind the `OffsetDateTime` as is to backends with a native datetime type
+macro_rules! native_date_time {
+    ($db:ty) => {
+        impl Type<$db> for DateTime {
+            fn type_info() -> <$db as Database>::TypeInfo {
+                <OffsetDateTime as Type<$db>>::type_info()
+            }
+        }
+
+        impl<'q> Encode<'q, $db> for DateTime {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <$db as Database>::ArgumentBuffer<'q>,
+            ) -> Result<IsNull, BoxDynError> {
+                Encode::<$db>::encode_by_ref(&self.0, buf)
+            }
+        }
+    };
+}
+
+native_date_time!(MySql);
+#[cfg(feature = "postgres")]
+native_date_time!(sqlx::Postgres);
+
+#[cfg(feature = "sqlite")]
+impl Type<Sqlite> for DateTime {
+    fn type_info() -> SqliteTypeInfo {
+        <OffsetDateTime as Type<Sqlite>>::type_info()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'q> Encode<'q, Sqlite> for DateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        let format = format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+        );
+        let text = self.0.to_offset(UtcOffset::UTC).format(&format)?;
+
+        Encode::<Sqlite>::encode(text, buf)
+    }
+}
 
 /// `GroupAuditLogEntry` is not strict
-#[derive(Clone, Debug, FromRow)]
+#[derive(Clone, Debug, Deserialize, FromRow, Serialize)]
 pub struct Log {
     pub id: String,
-    pub created_at: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
     pub group_id: String,
     pub actor_id: String,
     pub actor_display_name: Option<String>,
     pub target_id: Option<String>,
-    pub event_type: String,
+    #[sqlx(try_from = "String")]
+    pub event_type: EventType,
     pub description: String,
     pub data: Value,
 }
 
 /// Staff Member Leaderboard Statistics
-#[derive(Clone, Debug, FromRow)]
+#[derive(Clone, Debug, Default, FromRow)]
 pub struct StaffStats {
+    pub actor_id: String,
     pub username: String,
-    pub all_bans: i32,
-    pub all_kick: i32,
-    pub all_warn: i32,
-    pub new_bans: i32,
-    pub new_kick: i32,
-    pub new_warn: i32,
+    pub all_bans: i64,
+    pub all_kick: i64,
+    pub all_warn: i64,
+    pub new_bans: i64,
+    pub new_kick: i64,
+    pub new_warn: i64,
+}
+
+/// `VRChat` user profile cached for the embeds
+#[derive(Clone, Debug, FromRow)]
+pub struct CachedUser {
+    pub id:           String,
+    pub display_name: String,
+    pub user_icon:    String,
+    pub thumbnail:    String,
+    pub bio:          String,
+    pub badges:       Value,
+    pub updated_at:   OffsetDateTime,
+}
+
+impl CachedUser {
+    /// # Get the badge names
+    #[must_use]
+    pub fn badge_names(&self) -> Vec<&str> {
+        self.badges
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect()
+    }
+}
+
+/// `GroupAuditLogEntry` that failed to convert into a `Log`
+#[derive(Clone, Debug, FromRow)]
+pub struct Quarantined {
+    pub id:       String,
+    pub group_id: String,
+    pub entry:    Value,
+    pub error:    String,
+}
+
+impl Quarantined {
+    /// # Quarantine a raw audit log entry with the conversion error
+    /// Entries without an id are identified by a fingerprint of their contents.
+    #[must_use]
+    pub fn new(group_id: &str, entry: Value, error: &Report) -> Self {
+        let id = entry
+            .get("id")
+            .and_then(Value::as_str)
+            .map_or_else(|| fingerprint(&entry.to_string()), ToOwned::to_owned);
+
+        Self {
+            id,
+            group_id: group_id.to_owned(),
+            entry,
+            error: error.to_string(),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across builds unlike `DefaultHasher`
+fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    format!("fnv_{hash:016x}")
+}
+
+/// # Split a search into terms without the full-text query syntax characters
+#[must_use]
+pub fn search_terms(search: &str) -> Vec<String> {
+    search
+        .split_whitespace()
+        .map(|term| {
+            term.replace(
+                ['"', '*', '+', '-', '(', ')', '<', '>', '~', '@', ':', '^'],
+                "",
+            )
+        })
+        .filter(|term| !term.is_empty())
+        .collect()
 }
 
 /// Convert between `GroupAuditLogEntry` and `Log`
@@ -40,95 +218,516 @@ impl TryFrom<GroupAuditLogEntry> for Log {
     fn try_from(log: GroupAuditLogEntry) -> Result<Self, Self::Error> {
         Ok(Self {
             id: log.id.ok_or_eyre("id")?,
-            created_at: log.created_at.ok_or_eyre("created_at")?,
+            created_at: OffsetDateTime::parse(&log.created_at.ok_or_eyre("created_at")?, &Rfc3339)?,
             group_id: log.group_id.ok_or_eyre("group_id")?,
             actor_id: log.actor_id.ok_or_eyre("actor_id")?,
             actor_display_name: log.actor_display_name,
             target_id: log.target_id,
-            event_type: log.event_type.ok_or_eyre("event_type")?,
+            event_type: log.event_type.ok_or_eyre("event_type")?.into(),
             description: log.description.ok_or_eyre("description")?,
             data: serde_json::to_value(&log.data)?,
         })
     }
 }
 
+/// Convert a fetched `User` into a `CachedUser` updated now
+impl From<User> for CachedUser {
+    fn from(user: User) -> Self {
+        /* Fallback to the avatar thumbnail without VRC+ */
+        let mut thumbnail = user.profile_pic_override_thumbnail;
+        if thumbnail.is_empty() {
+            thumbnail = user.current_avatar_thumbnail_image_url;
+        }
+
+        let badges = user
+            .badges
+            .into_iter()
+            .flatten()
+            .map(|badge| Value::String(badge.badge_name))
+            .collect();
+
+        Self {
+            id: user.id,
+            display_name: user.display_name,
+            user_icon: user.user_icon,
+            thumbnail,
+            bio: user.bio,
+            badges: Value::Array(badges),
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
 impl LogsDB {
-    /// # Connect to the database
+    /// # Connect to the database and apply the migrations
+    /// The backend is selected by the url scheme (`mysql://`, `sqlite://` or `postgres://`).
     ///
     /// # Errors
-    /// Will return `Err` if `MySqlPool::connect` fails.
+    /// Will return `Err` if the scheme is unsupported, `connect` or `Migrator::run` fails.
     pub async fn connect(url: &str) -> Result<Self> {
-        MySqlPool::connect(url)
-            .await
-            .map(LogsDB)
+        let scheme = url.split_once(':').map_or(url, |(scheme, _)| scheme);
+        match scheme {
+            "mysql" | "mariadb" => {
+                let pool = MySqlPool::connect(url).await?;
+                sqlx::migrate!("./migrations/mysql").run(&pool).await?;
+
+                Ok(Self::MySql(pool))
+            }
+            #[cfg(feature = "sqlite")]
+            "sqlite" => {
+                let options = url.parse::<SqliteConnectOptions>()?.create_if_missing(true);
+                let pool = SqlitePool::connect_with(options).await?;
+                sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+                Ok(Self::Sqlite(pool))
+            }
+            #[cfg(feature = "postgres")]
+            "postgres" | "postgresql" => {
+                let pool = PgPool::connect(url).await?;
+                sqlx::migrate!("./migrations/postgres").run(&pool).await?;
+
+                Ok(Self::Postgres(pool))
+            }
+            _ => bail!("Unsupported database url scheme: {scheme}"),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for LogsDB {
+    async fn for_each_log(
+        &self,
+        filter: &LogFilter,
+        visit: &mut (dyn FnMut(Log) -> Result<()> + Send),
+    ) -> Result<u64> {
+        let mut count = 0;
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM logs WHERE 1 = 1");
+            if let Some(group_id) = &filter.group_id {
+                query.push(" AND group_id = ").push_bind(group_id);
+            }
+            if let Some(event_type) = &filter.event_type {
+                query.push(" AND event_type = ").push_bind(event_type.as_str());
+            }
+            if let Some(actor_id) = &filter.actor_id {
+                query.push(" AND actor_id = ").push_bind(actor_id);
+            }
+            if let Some(target_id) = &filter.target_id {
+                query.push(" AND target_id = ").push_bind(target_id);
+            }
+            if let Some(start) = filter.start {
+                query.push(" AND created_at >= ").push_bind(DateTime(start));
+            }
+            if let Some(end) = filter.end {
+                query.push(" AND created_at < ").push_bind(DateTime(end));
+            }
+            query.push(" ORDER BY created_at, id");
+
+            /* Stream the rows instead of using `fetch_all` */
+            let mut rows = query.build_query_as().fetch(pool);
+            while let Some(log) = rows.try_next().await? {
+                visit(log)?;
+                count += 1;
+            }
+        });
+
+        Ok(count)
+    }
+
+    async fn get_latest_created_at(&self, group_id: &str) -> Result<Option<OffsetDateTime>> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT MAX(created_at) FROM logs WHERE group_id = ");
+            query.push_bind(group_id);
+
+            query.build_query_scalar().fetch_one(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn get_latest_actions(
+        &self,
+        group_id: &str,
+        target_id: Option<&str>,
+        after: Option<&Log>,
+        limit: i64,
+    ) -> Result<Vec<Log>> {
+        let ban = EventType::Ban.as_str();
+        let unban = EventType::Unban.as_str();
+        with_pool!(self, |pool| {
+            /* Skip the logs that have a newer action for the same target */
+            let mut query = QueryBuilder::new("SELECT l.* FROM logs l WHERE l.group_id = ");
+            query.push_bind(group_id);
+            query.push(" AND l.target_id IS NOT NULL AND l.event_type IN (");
+            query.push_bind(ban).push(", ").push_bind(unban);
+            query.push(
+                r") AND NOT EXISTS (
+                    SELECT 1 FROM logs n
+                    WHERE n.group_id = l.group_id AND n.target_id = l.target_id
+                    AND (n.created_at > l.created_at
+                        OR (n.created_at = l.created_at AND n.id > l.id))
+                    AND n.event_type IN (",
+            );
+            query.push_bind(ban).push(", ").push_bind(unban).push("))");
+            if let Some(target_id) = target_id {
+                query.push(" AND l.target_id = ").push_bind(target_id);
+            }
+            if let Some(after) = after {
+                query.push(" AND (l.created_at < ").push_bind(DateTime(after.created_at));
+                query.push(" OR (l.created_at = ").push_bind(DateTime(after.created_at));
+                query.push(" AND l.id < ").push_bind(&after.id).push("))");
+            }
+            query.push(" ORDER BY l.created_at DESC, l.id DESC LIMIT ");
+            query.push_bind(limit);
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn get_history(&self, group_id: &str, user_id: &str) -> Result<Vec<Log>> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM logs WHERE group_id = ");
+            query.push_bind(group_id);
+            query.push(" AND (target_id = ").push_bind(user_id);
+            query.push(" OR (actor_id = ").push_bind(user_id);
+            query.push(" AND event_type IN (");
+            query.push_bind(EventType::Join.as_str());
+            query.push(", ");
+            query.push_bind(EventType::Leave.as_str());
+            query.push("))) ORDER BY created_at DESC");
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn get_actions_by_actor(
+        &self,
+        group_id: &str,
+        actor_id: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+    ) -> Result<Vec<Log>> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM logs WHERE group_id = ");
+            query.push_bind(group_id);
+            query.push(" AND actor_id = ").push_bind(actor_id);
+            if let Some(event_type) = event_type {
+                query.push(" AND event_type = ").push_bind(event_type.as_str());
+            }
+            if let Some(start) = start {
+                query.push(" AND created_at >= ").push_bind(DateTime(start));
+            }
+            if let Some(end) = end {
+                query.push(" AND created_at < ").push_bind(DateTime(end));
+            }
+            query.push(" ORDER BY created_at DESC");
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn search_logs(
+        &self,
+        group_id: &str,
+        search: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        limit: i64,
+    ) -> Result<Vec<Log>> {
+        /* Quote the terms so the input can't use the full-text query syntax */
+        let terms = search_terms(search);
+        if terms.is_empty() {
+            bail!("Empty search")
+        }
+
+        let (search, matches, close) = match self {
+            Self::MySql(_) => (
+                terms
+                    .iter()
+                    .map(|term| format!("+\"{term}\""))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                " AND MATCH (description, data_text) AGAINST (",
+                " IN BOOLEAN MODE)",
+            ),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => (
+                terms
+                    .iter()
+                    .map(|term| format!("\"{term}\""))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                " AND rowid IN (SELECT rowid FROM logs_fts WHERE logs_fts MATCH ",
+                ")",
+            ),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(_) => (
+                terms.join(" "),
+                " AND search @@ plainto_tsquery('english', ",
+                ")",
+            ),
+        };
+
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM logs WHERE group_id = ");
+            query.push_bind(group_id);
+            query.push(matches).push_bind(&search).push(close);
+            if let Some(event_type) = event_type {
+                query.push(" AND event_type = ").push_bind(event_type.as_str());
+            }
+            if let Some(start) = start {
+                query.push(" AND created_at >= ").push_bind(DateTime(start));
+            }
+            if let Some(end) = end {
+                query.push(" AND created_at < ").push_bind(DateTime(end));
+            }
+            query.push(" ORDER BY created_at DESC LIMIT ").push_bind(limit);
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn get_staff_stats(
+        &self,
+        group_id: &str,
+        recent: OffsetDateTime,
+    ) -> Result<Vec<StaffStats>> {
+        let bans = [EventType::Ban.as_str()];
+        let kick = [EventType::Kick.as_str(), EventType::InstanceKick.as_str()];
+        let warn = [EventType::InstanceWarn.as_str()];
+
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new(
+                "SELECT actor_id, COALESCE(MAX(actor_display_name), actor_id) AS username",
+            );
+
+            /* Count every action, then only the recent actions */
+            for (prefix, since) in [("all", None), ("new", Some(recent))] {
+                for (name, event_types) in [("bans", &bans[..]), ("kick", &kick), ("warn", &warn)] {
+                    query.push(", COUNT(CASE WHEN event_type IN (");
+                    let mut separated = query.separated(", ");
+                    for event_type in event_types {
+                        separated.push_bind(*event_type);
+                    }
+                    query.push(")");
+                    if let Some(since) = since {
+                        query.push(" AND created_at >= ").push_bind(DateTime(since));
+                    }
+                    query.push(format!(" THEN 1 END) AS {prefix}_{name}"));
+                }
+            }
+
+            query.push(" FROM logs WHERE group_id = ").push_bind(group_id);
+            query.push(" GROUP BY actor_id");
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn purge_logs(
+        &self,
+        event_type: &EventType,
+        before: OffsetDateTime,
+        dry_run: bool,
+    ) -> Result<u64> {
+        let statement = if dry_run {
+            "SELECT COUNT(*) FROM logs"
+        } else {
+            "DELETE FROM logs"
+        };
+
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new(statement);
+            query.push(" WHERE event_type = ").push_bind(event_type.as_str());
+            query.push(" AND created_at < ").push_bind(DateTime(before));
+
+            if dry_run {
+                let count: i64 = query.build_query_scalar().fetch_one(pool).await?;
+                u64::try_from(count).map_err(Report::msg)
+            } else {
+                let result = query.build().execute(pool).await?;
+                Ok(result.rows_affected())
+            }
+        })
+    }
+
+    async fn insert_quarantined(&self, quarantined: &Quarantined) -> Result<bool> {
+        let mysql = matches!(self, Self::MySql(_));
+        with_pool!(self, |pool| {
+            let mut query =
+                QueryBuilder::new("INSERT INTO quarantine (id, group_id, entry, error)");
+            query.push(if mysql { " SELECT " } else { " VALUES (" });
+            let mut separated = query.separated(", ");
+            separated.push_bind(&quarantined.id);
+            separated.push_bind(&quarantined.group_id);
+            separated.push_bind(&quarantined.entry);
+            separated.push_bind(&quarantined.error);
+
+            if mysql {
+                query.push(" FROM DUAL WHERE NOT EXISTS (SELECT 1 FROM quarantine WHERE id = ");
+                query.push_bind(&quarantined.id).push(")");
+            } else {
+                query.push(") ON CONFLICT (id) DO NOTHING");
+            }
+
+            if query.build().execute(pool).await?.rows_affected() > 0 {
+                return Ok(true);
+            }
+
+            /* Already quarantined, keep the latest error */
+            let mut query = QueryBuilder::new("UPDATE quarantine SET error = ");
+            query.push_bind(&quarantined.error);
+            query.push(" WHERE id = ").push_bind(&quarantined.id);
+            query.build().execute(pool).await?;
+
+            Ok(false)
+        })
+    }
+
+    async fn get_quarantined(&self) -> Result<Vec<Quarantined>> {
+        let query = "SELECT id, group_id, entry, error FROM quarantine";
+        with_pool!(self, |pool| sqlx::query_as(query).fetch_all(pool).await).map_err(Report::msg)
+    }
+
+    async fn count_quarantined(&self) -> Result<i64> {
+        let query = "SELECT COUNT(*) FROM quarantine";
+        with_pool!(self, |pool| sqlx::query_scalar(query).fetch_one(pool).await)
             .map_err(Report::msg)
     }
 
-    /// # Get the most recent matching action logs (bans and pardons)
-    ///
-    /// # Errors
-    /// Will return `Err` if `sqlx::query_as` fails.
-    pub async fn get_recent_actions_by_id(&self, target_id: &str) -> Result<Vec<Log>> {
-        sqlx::query_as(
-            r"
-                SELECT * FROM logs
-                WHERE target_id = ? AND (event_type = 'group.user.ban' OR event_type = 'group.user.unban')
-                ORDER BY created_at DESC
-             ",
-        )
-        .bind(target_id)
-        .fetch_all(&self.0)
-        .await
+    async fn remove_quarantined(&self, id: &str) -> Result<()> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("DELETE FROM quarantine WHERE id = ");
+            query.push_bind(id);
+
+            query.build().execute(pool).await.map(|_| ())
+        })
         .map_err(Report::msg)
     }
 
-    /// # Get all the action logs sorted by most recent.
-    ///
-    /// # Errors
-    /// Will return `Err` if `sqlx::query_as` fails.
-    pub async fn get_all_recent_actions(&self) -> Result<Vec<Log>> {
-        sqlx::query_as(
-            r"
-                SELECT * FROM logs
-                WHERE event_type IN ('group.user.ban','group.user.unban')
-                ORDER BY created_at DESC
-             ",
-        )
-        .fetch_all(&self.0)
-        .await
+    async fn get_user(&self, user_id: &str) -> Result<Option<CachedUser>> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM users WHERE id = ");
+            query.push_bind(user_id);
+
+            query.build_query_as().fetch_optional(pool).await
+        })
         .map_err(Report::msg)
     }
 
-    /// # Insert a log into the database
-    ///
-    /// # Errors
-    /// Will return `Err` if `sqlx::query` fails.
-    pub async fn insert_log(&self, log: Log) -> Result<MySqlQueryResult> {
-        sqlx::query(
-            r"INSERT INTO logs (
-                id,
-                created_at,
-                group_id,
-                actor_id,
-                actor_display_name,
-                target_id,
-                event_type,
-                description,
-                data
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        )
-        .bind(log.id)
-        .bind(log.created_at)
-        .bind(log.group_id)
-        .bind(log.actor_id)
-        .bind(log.actor_display_name)
-        .bind(log.target_id)
-        .bind(log.event_type)
-        .bind(log.description)
-        .bind(log.data)
-        .execute(&self.0)
-        .await
+    async fn get_stale_users(
+        &self,
+        before: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<CachedUser>> {
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new("SELECT * FROM users WHERE updated_at < ");
+            query.push_bind(DateTime(before));
+            query.push(" ORDER BY updated_at LIMIT ").push_bind(limit);
+
+            query.build_query_as().fetch_all(pool).await
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn upsert_user(&self, user: &CachedUser) -> Result<()> {
+        let upsert = match self {
+            Self::MySql(_) => {
+                r" ON DUPLICATE KEY UPDATE
+                display_name = VALUES(display_name),
+                user_icon = VALUES(user_icon),
+                thumbnail = VALUES(thumbnail),
+                bio = VALUES(bio),
+                badges = VALUES(badges),
+                updated_at = VALUES(updated_at)"
+            }
+            #[cfg(any(feature = "sqlite", feature = "postgres"))]
+            _ => {
+                r" ON CONFLICT (id) DO UPDATE SET
+                display_name = excluded.display_name,
+                user_icon = excluded.user_icon,
+                thumbnail = excluded.thumbnail,
+                bio = excluded.bio,
+                badges = excluded.badges,
+                updated_at = excluded.updated_at"
+            }
+        };
+
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new(
+                r"INSERT INTO users (
+                    id,
+                    display_name,
+                    user_icon,
+                    thumbnail,
+                    bio,
+                    badges,
+                    updated_at
+                ) VALUES (",
+            );
+            let mut separated = query.separated(", ");
+            separated.push_bind(&user.id);
+            separated.push_bind(&user.display_name);
+            separated.push_bind(&user.user_icon);
+            separated.push_bind(&user.thumbnail);
+            separated.push_bind(&user.bio);
+            separated.push_bind(&user.badges);
+            separated.push_bind(DateTime(user.updated_at));
+            query.push(")").push(upsert);
+
+            query.build().execute(pool).await.map(|_| ())
+        })
+        .map_err(Report::msg)
+    }
+
+    async fn insert_log(&self, log: &Log) -> Result<bool> {
+        let mysql = matches!(self, Self::MySql(_));
+        with_pool!(self, |pool| {
+            let mut query = QueryBuilder::new(
+                r"INSERT INTO logs (
+                    id,
+                    created_at,
+                    group_id,
+                    actor_id,
+                    actor_display_name,
+                    target_id,
+                    event_type,
+                    description,
+                    data
+                )",
+            );
+            query.push(if mysql { " SELECT " } else { " VALUES (" });
+            let mut separated = query.separated(", ");
+            separated.push_bind(&log.id);
+            separated.push_bind(DateTime(log.created_at));
+            separated.push_bind(&log.group_id);
+            separated.push_bind(&log.actor_id);
+            separated.push_bind(&log.actor_display_name);
+            separated.push_bind(&log.target_id);
+            separated.push_bind(log.event_type.as_str());
+            separated.push_bind(&log.description);
+            separated.push_bind(&log.data);
+
+            /* Only skip duplicate ids, `INSERT IGNORE` also hides truncation and NULL errors */
+            if mysql {
+                query.push(" FROM DUAL WHERE NOT EXISTS (SELECT 1 FROM logs WHERE id = ");
+                query.push_bind(&log.id).push(")");
+            } else {
+                query.push(") ON CONFLICT (id) DO NOTHING");
+            }
+
+            query
+                .build()
+                .execute(pool)
+                .await
+                .map(|result| result.rows_affected() > 0)
+        })
         .map_err(Report::msg)
     }
 }
@@ -1,15 +1,44 @@
-use color_eyre::Result;
+use std::sync::Arc;
+
+use color_eyre::{eyre::bail, Result};
 use derive_config::DeriveTomlConfig;
 use poise::{serenity_prelude::*, Framework, FrameworkOptions};
-use vrc_ban::{commands::prelude::*, config::Config, logsdb::LogsDB, vrchat::VRChat, Data};
+use vrc_ban::{
+    commands::prelude::*,
+    config::Config,
+    export,
+    import,
+    pipeline,
+    purge,
+    storage,
+    sync::SyncHandle,
+    users,
+    vrchat::VRChat,
+    Data,
+};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
-    /* Load Config, LogsDB, and VRChat */
+    /* Load Config, Storage, and VRChat */
     let mut config = Config::load()?;
-    let logsdb = LogsDB::connect(&config.sql_secret).await?;
+    if config.migrate() {
+        config.save()?;
+    }
+
+    let logsdb = storage::connect(&config.sql_secret).await?;
+
+    /* Run a CLI subcommand instead of the bot */
+    let mut args = std::env::args().skip(1);
+    if let Some(command) = args.next() {
+        return match command.as_str() {
+            "export" => export::cli(logsdb.as_ref(), args.collect()).await,
+            "import" => import::cli(logsdb.as_ref(), args.collect()).await,
+            _ => bail!("Unknown subcommand: {command}"),
+        };
+    }
+
     let vrchat = VRChat::new(
         &config.vrc_cookies,
         &config.vrc_username,
@@ -22,18 +51,45 @@ async fn main() -> Result<()> {
     config.vrc_cookies = vrchat.get_cookies();
     config.save()?;
 
+    /* Start syncing the audit logs in the background */
+    let vrchat = Arc::new(vrchat);
+    let http = Arc::new(Http::new(&config.bot_secret));
+    let sync_handle = SyncHandle::spawn(http, config.clone(), logsdb.clone(), vrchat.clone());
+
+    /* Listen to the VRChat pipeline for real-time group events */
+    if config.pipeline_enabled {
+        let pipeline = pipeline::run(config.clone(), vrchat.clone(), sync_handle.clone());
+        tokio::spawn(pipeline);
+    }
+
+    /* Purge the logs past their retention in the background */
+    tokio::spawn(purge::run(config.clone(), logsdb.clone()));
+
+    /* Refresh the cached user profiles in the background */
+    tokio::spawn(users::run(config.clone(), logsdb.clone(), vrchat.clone()));
+
     let framework = {
         let config = config.clone();
+        let sync_handle = sync_handle.clone();
         Framework::builder()
             .options(FrameworkOptions {
-                commands: vec![cheers(), pardon(), help()],
-                event_handler: |ctx, event, framework, data| {
-                    Box::pin(data.event_handler(ctx, event, framework))
-                },
+                commands: vec![
+                    cheers(),
+                    pardon(),
+                    history(),
+                    leaderboard(),
+                    logs(),
+                    staff(),
+                    purge(),
+                    sync(),
+                    help(),
+                ],
                 ..Default::default()
             })
             .setup(move |ctx, ready, framework| {
-                Box::pin(Data::new(config, logsdb, vrchat).setup(ctx, ready, framework))
+                Box::pin(
+                    Data::new(config, logsdb, vrchat, sync_handle).setup(ctx, ready, framework),
+                )
             })
             .build()
     };
@@ -43,7 +99,16 @@ async fn main() -> Result<()> {
         .framework(framework)
         .await?;
 
+    /* Shutdown cleanly on Ctrl+C */
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            shard_manager.shutdown_all().await;
+        }
+    });
+
     client.start().await?;
+    sync_handle.stop().await;
 
     Ok(())
 }
@@ -0,0 +1,325 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
+use async_trait::async_trait;
+use color_eyre::{eyre::bail, Result};
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+
+use crate::{
+    event::EventType,
+    logsdb::{search_terms, CachedUser, Log, Quarantined, StaffStats},
+    storage::{LogFilter, Storage},
+};
+
+/// In-memory storage for tests and dry runs
+#[derive(Debug, Default)]
+pub struct MemoryDB {
+    logs:       RwLock<BTreeMap<String, Log>>,
+    quarantine: RwLock<HashMap<String, Quarantined>>,
+    users:      RwLock<HashMap<String, CachedUser>>,
+}
+
+#[async_trait]
+impl Storage for MemoryDB {
+    async fn insert_log(&self, log: &Log) -> Result<bool> {
+        let mut logs = self.logs.write().await;
+        let new = !logs.contains_key(&log.id);
+        if new {
+            logs.insert(log.id.clone(), log.clone());
+        }
+
+        drop(logs);
+
+        Ok(new)
+    }
+
+    async fn for_each_log(
+        &self,
+        filter: &LogFilter,
+        visit: &mut (dyn FnMut(Log) -> Result<()> + Send),
+    ) -> Result<u64> {
+        let mut logs = self
+            .logs
+            .read()
+            .await
+            .values()
+            .filter(|log| filter.matches(log))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        logs.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        let count = u64::try_from(logs.len())?;
+        logs.into_iter().try_for_each(visit)?;
+
+        Ok(count)
+    }
+
+    async fn get_latest_created_at(&self, group_id: &str) -> Result<Option<OffsetDateTime>> {
+        let latest = self
+            .logs
+            .read()
+            .await
+            .values()
+            .filter(|log| log.group_id == group_id)
+            .map(|log| log.created_at)
+            .max();
+
+        Ok(latest)
+    }
+
+    async fn get_latest_actions(
+        &self,
+        group_id: &str,
+        target_id: Option<&str>,
+        after: Option<&Log>,
+        limit: i64,
+    ) -> Result<Vec<Log>> {
+        /* Keep the newest action of each target */
+        let mut latest = HashMap::<String, Log>::new();
+        let logs = self.logs.read().await;
+        let actions = logs
+            .values()
+            .filter(|log| log.group_id == group_id)
+            .filter(|log| matches!(log.event_type, EventType::Ban | EventType::Unban));
+
+        for log in actions {
+            let Some(log_target_id) = log.target_id.as_deref() else {
+                continue;
+            };
+
+            if target_id.is_some_and(|target_id| target_id != log_target_id) {
+                continue;
+            }
+
+            let key = (log.created_at, &log.id);
+            let newer = latest
+                .get(log_target_id)
+                .is_none_or(|other| key > (other.created_at, &other.id));
+
+            if newer {
+                latest.insert(log_target_id.to_owned(), log.clone());
+            }
+        }
+
+        drop(logs);
+
+        let mut logs = latest
+            .into_values()
+            .filter(|log| {
+                after.is_none_or(|after| (log.created_at, &log.id) < (after.created_at, &after.id))
+            })
+            .collect::<Vec<_>>();
+
+        logs.sort_by(|a, b| (b.created_at, &b.id).cmp(&(a.created_at, &a.id)));
+        logs.truncate(usize::try_from(limit)?);
+
+        Ok(logs)
+    }
+
+    async fn get_history(&self, group_id: &str, user_id: &str) -> Result<Vec<Log>> {
+        let mut logs = self
+            .logs
+            .read()
+            .await
+            .values()
+            .filter(|log| log.group_id == group_id)
+            .filter(|log| {
+                log.target_id.as_deref() == Some(user_id)
+                    || (log.actor_id == user_id
+                        && matches!(log.event_type, EventType::Join | EventType::Leave))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        logs.sort_by_key(|log| Reverse(log.created_at));
+
+        Ok(logs)
+    }
+
+    async fn get_actions_by_actor(
+        &self,
+        group_id: &str,
+        actor_id: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+    ) -> Result<Vec<Log>> {
+        let mut logs = self
+            .logs
+            .read()
+            .await
+            .values()
+            .filter(|log| log.group_id == group_id && log.actor_id == actor_id)
+            .filter(|log| event_type.is_none_or(|event_type| &log.event_type == event_type))
+            .filter(|log| start.is_none_or(|start| log.created_at >= start))
+            .filter(|log| end.is_none_or(|end| log.created_at < end))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        logs.sort_by_key(|log| Reverse(log.created_at));
+
+        Ok(logs)
+    }
+
+    async fn search_logs(
+        &self,
+        group_id: &str,
+        search: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        limit: i64,
+    ) -> Result<Vec<Log>> {
+        let terms = search_terms(search)
+            .into_iter()
+            .map(|term| term.to_lowercase())
+            .collect::<Vec<_>>();
+
+        if terms.is_empty() {
+            bail!("Empty search")
+        }
+
+        let mut logs = self
+            .logs
+            .read()
+            .await
+            .values()
+            .filter(|log| log.group_id == group_id)
+            .filter(|log| event_type.is_none_or(|event_type| &log.event_type == event_type))
+            .filter(|log| start.is_none_or(|start| log.created_at >= start))
+            .filter(|log| end.is_none_or(|end| log.created_at < end))
+            .filter(|log| {
+                let text = format!("{} {}", log.description, log.data).to_lowercase();
+                terms.iter().all(|term| text.contains(term))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        logs.sort_by_key(|log| Reverse(log.created_at));
+        logs.truncate(usize::try_from(limit)?);
+
+        Ok(logs)
+    }
+
+    async fn get_staff_stats(
+        &self,
+        group_id: &str,
+        recent: OffsetDateTime,
+    ) -> Result<Vec<StaffStats>> {
+        let mut stats = HashMap::<String, StaffStats>::new();
+        for log in self.logs.read().await.values() {
+            if log.group_id != group_id {
+                continue;
+            }
+
+            let actor = stats
+                .entry(log.actor_id.clone())
+                .or_insert_with(|| StaffStats {
+                    actor_id: log.actor_id.clone(),
+                    username: log.actor_id.clone(),
+                    ..Default::default()
+                });
+
+            if let Some(display_name) = &log.actor_display_name {
+                actor.username.clone_from(display_name);
+            }
+
+            let new = i64::from(log.created_at >= recent);
+            match log.event_type {
+                EventType::Ban => {
+                    actor.all_bans += 1;
+                    actor.new_bans += new;
+                }
+                EventType::Kick | EventType::InstanceKick => {
+                    actor.all_kick += 1;
+                    actor.new_kick += new;
+                }
+                EventType::InstanceWarn => {
+                    actor.all_warn += 1;
+                    actor.new_warn += new;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(stats.into_values().collect())
+    }
+
+    async fn purge_logs(
+        &self,
+        event_type: &EventType,
+        before: OffsetDateTime,
+        dry_run: bool,
+    ) -> Result<u64> {
+        let expired = |log: &Log| &log.event_type == event_type && log.created_at < before;
+        let mut logs = self.logs.write().await;
+        let count = logs.values().filter(|log| expired(log)).count();
+        if !dry_run {
+            logs.retain(|_, log| !expired(log));
+        }
+
+        drop(logs);
+
+        Ok(u64::try_from(count)?)
+    }
+
+    async fn insert_quarantined(&self, quarantined: &Quarantined) -> Result<bool> {
+        let id = quarantined.id.clone();
+        let previous = self
+            .quarantine
+            .write()
+            .await
+            .insert(id, quarantined.clone());
+
+        Ok(previous.is_none())
+    }
+
+    async fn get_quarantined(&self) -> Result<Vec<Quarantined>> {
+        Ok(self.quarantine.read().await.values().cloned().collect())
+    }
+
+    async fn count_quarantined(&self) -> Result<i64> {
+        Ok(i64::try_from(self.quarantine.read().await.len())?)
+    }
+
+    async fn remove_quarantined(&self, id: &str) -> Result<()> {
+        self.quarantine.write().await.remove(id);
+
+        Ok(())
+    }
+
+    async fn get_user(&self, user_id: &str) -> Result<Option<CachedUser>> {
+        Ok(self.users.read().await.get(user_id).cloned())
+    }
+
+    async fn get_stale_users(
+        &self,
+        before: OffsetDateTime,
+        limit: i64,
+    ) -> Result<Vec<CachedUser>> {
+        let mut users = self
+            .users
+            .read()
+            .await
+            .values()
+            .filter(|user| user.updated_at < before)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        users.sort_by_key(|user| user.updated_at);
+        users.truncate(usize::try_from(limit)?);
+
+        Ok(users)
+    }
+
+    async fn upsert_user(&self, user: &CachedUser) -> Result<()> {
+        let id = user.id.clone();
+        self.users.write().await.insert(id, user.clone());
+
+        Ok(())
+    }
+}
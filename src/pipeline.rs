@@ -0,0 +1,152 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::{eyre::OptionExt, Result};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::{
+    client::IntoClientRequest,
+    handshake::client::Request,
+    http::{header::USER_AGENT, HeaderValue},
+    Message,
+};
+
+use crate::{
+    config::{Config, Group},
+    sync::{Backoff, SyncHandle},
+    vrchat::VRChat,
+};
+
+/// `VRChat` pipeline websocket message
+#[derive(Clone, Debug, Deserialize)]
+pub struct PipelineMessage {
+    #[serde(rename = "type")]
+    pub kind:    String,
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl PipelineMessage {
+    /// # Parse a pipeline message
+    /// The content is a JSON encoded string, it's decoded when possible.
+    ///
+    /// # Errors
+    /// Will return `Err` if `serde_json::from_str` fails.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut message: Self = serde_json::from_str(text)?;
+        if let Value::String(content) = &message.content {
+            if let Ok(content) = serde_json::from_str(content) {
+                message.content = content;
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// # Check if the message is a group event
+    #[must_use]
+    pub fn is_group_event(&self) -> bool {
+        self.kind.starts_with("group-")
+    }
+
+    /// # Get the group id the message is about, if any
+    #[must_use]
+    pub fn group_id(&self) -> Option<&str> {
+        ["/groupId", "/member/groupId", "/role/groupId"]
+            .into_iter()
+            .find_map(|pointer| self.content.pointer(pointer)?.as_str())
+    }
+}
+
+/// # Listen to the `VRChat` pipeline and sync on group events
+/// Reconnects with exponential backoff and jitter, polling keeps running
+/// in the background as a fallback.
+pub async fn run(config: Config, vrchat: Arc<VRChat>, sync: SyncHandle) {
+    let max_backoff = Duration::from_secs(config.sync_max_backoff);
+    let mut backoff = Backoff::new(Duration::from_secs(5), max_backoff);
+
+    let mut trigger = |group_id: Option<&str>| sync.trigger(group_id);
+
+    loop {
+        let request = vrchat
+            .get_auth_token()
+            .ok_or_eyre("Missing auth cookie")
+            .and_then(|token| request(&config.pipeline_url, &token, vrchat.get_user_agent()));
+
+        let result = match request {
+            Ok(request) => listen(request, &config.vrc_groups, &mut backoff, &mut trigger).await,
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            eprintln!("Error: Pipeline: {error}");
+        }
+
+        let delay = backoff.fail();
+        eprintln!(
+            "Pipeline disconnected, reconnecting in {}s",
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// # Create the pipeline websocket request with the auth token and user agent
+///
+/// # Errors
+/// Will return `Err` if the url or user agent is invalid.
+pub fn request(url: &str, token: &str, user_agent: Option<&str>) -> Result<Request> {
+    let mut request = format!("{url}?authToken={token}").into_client_request()?;
+    if let Some(user_agent) = user_agent {
+        let user_agent = HeaderValue::from_str(user_agent)?;
+        request.headers_mut().insert(USER_AGENT, user_agent);
+    }
+
+    Ok(request)
+}
+
+/// # Listen to the pipeline until it disconnects and trigger a sync on group events
+/// Events about unknown groups are ignored, events without a group trigger every group.
+///
+/// # Errors
+/// Will return `Err` if connecting, reading or `trigger` fails.
+pub async fn listen(
+    request: Request,
+    groups: &[Group],
+    backoff: &mut Backoff,
+    trigger: &mut (dyn FnMut(Option<&str>) -> Result<()> + Send),
+) -> Result<()> {
+    let (mut stream, _) = tokio_tungstenite::connect_async(request).await?;
+    println!("Pipeline connected");
+    backoff.reset();
+
+    while let Some(message) = stream.next().await {
+        let Message::Text(text) = message? else {
+            continue; // Pings are answered automatically
+        };
+
+        let message = match PipelineMessage::parse(&text) {
+            Ok(message) => message,
+            Err(error) => {
+                eprintln!("Error: Pipeline: {error}");
+                continue;
+            }
+        };
+
+        if !message.is_group_event() {
+            continue;
+        }
+
+        /* Sync the group the event is about, or every group if it's unknown */
+        let group_id = message.group_id();
+        if let Some(group_id) = group_id {
+            if !groups.iter().any(|g| g.group_id == group_id) {
+                continue;
+            }
+        }
+
+        trigger(group_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use time::OffsetDateTime;
+
+use crate::{
+    config::{Config, Retention},
+    event::EventType,
+    storage::Storage,
+};
+
+/// Logs purged (or that would be purged) per retention rule
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub purged:  Vec<(EventType, u64)>,
+}
+
+impl PurgeReport {
+    /// # Get the total number of logs purged
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.purged.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for PurgeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verb = if self.dry_run {
+            "Would purge"
+        } else {
+            "Purged"
+        };
+        write!(f, "{verb} {} logs", self.total())?;
+        for (event_type, count) in &self.purged {
+            write!(f, "\n{event_type}: {count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// # Purge the logs that are older than their retention rule
+///
+/// # Errors
+/// Will return `Err` if `purge_logs` fails.
+pub async fn purge(
+    logsdb: &dyn Storage,
+    retention: &[Retention],
+    dry_run: bool,
+) -> Result<PurgeReport> {
+    let now = OffsetDateTime::now_utc();
+    let mut report = PurgeReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    for rule in retention {
+        let before = now - time::Duration::days(rule.days);
+        let count = logsdb.purge_logs(&rule.event_type, before, dry_run).await?;
+        report.purged.push((rule.event_type.clone(), count));
+    }
+
+    Ok(report)
+}
+
+/// # Purge the logs on the configured interval
+/// Does nothing without retention rules.
+pub async fn run(config: Config, logsdb: Arc<dyn Storage>) {
+    if config.retention.is_empty() {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(config.purge_interval));
+    loop {
+        interval.tick().await;
+        match purge(logsdb.as_ref(), &config.retention, config.purge_dry_run).await {
+            Ok(report) => println!("{report}"),
+            Err(error) => eprintln!("Error: Purge: {error}"),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use color_eyre::Result;
+use time::OffsetDateTime;
+
+use crate::{
+    event::EventType,
+    logsdb::{CachedUser, Log, LogsDB, Quarantined, StaffStats},
+    memorydb::MemoryDB,
+};
+
+/// Optional filters for the logs, every filter that is set has to match
+#[derive(Clone, Debug, Default)]
+pub struct LogFilter {
+    pub group_id:   Option<String>,
+    pub event_type: Option<EventType>,
+    pub actor_id:   Option<String>,
+    pub target_id:  Option<String>,
+    pub start:      Option<OffsetDateTime>,
+    pub end:        Option<OffsetDateTime>,
+}
+
+impl LogFilter {
+    /// # Check if the log matches the filter
+    #[must_use]
+    pub fn matches(&self, log: &Log) -> bool {
+        self.group_id.as_ref().is_none_or(|id| &log.group_id == id)
+            && self
+                .event_type
+                .as_ref()
+                .is_none_or(|event_type| &log.event_type == event_type)
+            && self.actor_id.as_ref().is_none_or(|id| &log.actor_id == id)
+            && self
+                .target_id
+                .as_ref()
+                .is_none_or(|id| log.target_id.as_ref() == Some(id))
+            && self.start.is_none_or(|start| log.created_at >= start)
+            && self.end.is_none_or(|end| log.created_at < end)
+    }
+}
+
+/// Audit log storage backend
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// # Insert a log into the storage
+    /// Returns `false` if the log was already in the storage.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn insert_log(&self, log: &Log) -> Result<bool>;
+
+    /// # Visit every log matching the filter (oldest first) without loading them all
+    /// Returns the number of logs visited.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend or `visit` fails.
+    async fn for_each_log(
+        &self,
+        filter: &LogFilter,
+        visit: &mut (dyn FnMut(Log) -> Result<()> + Send),
+    ) -> Result<u64>;
+
+    /// # Get the creation date of the groups newest log
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_latest_created_at(&self, group_id: &str) -> Result<Option<OffsetDateTime>>;
+
+    /// # Get the latest action log (ban or pardon) of each target sorted by most recent
+    /// Keyset paginated, `after` is the last log of the previous page.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_latest_actions(
+        &self,
+        group_id: &str,
+        target_id: Option<&str>,
+        after: Option<&Log>,
+        limit: i64,
+    ) -> Result<Vec<Log>>;
+
+    /// # Get every log that targeted the user sorted by most recent
+    /// Joins and leaves are included, where the user is the actor.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_history(&self, group_id: &str, user_id: &str) -> Result<Vec<Log>>;
+
+    /// # Get the logs of a staff members actions sorted by most recent
+    /// Optionally only of an event type and between two dates (start inclusive, end exclusive).
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_actions_by_actor(
+        &self,
+        group_id: &str,
+        actor_id: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+    ) -> Result<Vec<Log>>;
+
+    /// # Search the logs descriptions and data sorted by most recent
+    /// Every search term has to match, optionally only of an event type and between two dates.
+    ///
+    /// # Errors
+    /// Will return `Err` if the search is empty or the backend fails.
+    async fn search_logs(
+        &self,
+        group_id: &str,
+        search: &str,
+        event_type: Option<&EventType>,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        limit: i64,
+    ) -> Result<Vec<Log>>;
+
+    /// # Get the ban, kick and warn statistics of every staff member
+    /// Actions since `recent` are also counted separately.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_staff_stats(
+        &self,
+        group_id: &str,
+        recent: OffsetDateTime,
+    ) -> Result<Vec<StaffStats>>;
+
+    /// # Delete the logs of an event type created before the date
+    /// Returns the number of logs deleted, or that would be deleted with `dry_run`.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn purge_logs(
+        &self,
+        event_type: &EventType,
+        before: OffsetDateTime,
+        dry_run: bool,
+    ) -> Result<u64>;
+
+    /// # Quarantine an audit log entry that failed to convert
+    /// Returns `false` if the entry was already quarantined, then only the error is updated.
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn insert_quarantined(&self, quarantined: &Quarantined) -> Result<bool>;
+
+    /// # Get all the quarantined audit log entries
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_quarantined(&self) -> Result<Vec<Quarantined>>;
+
+    /// # Count the quarantined audit log entries
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn count_quarantined(&self) -> Result<i64>;
+
+    /// # Remove an audit log entry from quarantine
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn remove_quarantined(&self, id: &str) -> Result<()>;
+
+    /// # Get a cached user profile
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_user(&self, user_id: &str) -> Result<Option<CachedUser>>;
+
+    /// # Get the cached user profiles updated before the date (oldest first)
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn get_stale_users(&self, before: OffsetDateTime, limit: i64)
+        -> Result<Vec<CachedUser>>;
+
+    /// # Insert or replace a cached user profile
+    ///
+    /// # Errors
+    /// Will return `Err` if the backend fails.
+    async fn upsert_user(&self, user: &CachedUser) -> Result<()>;
+}
+
+/// # Connect to the storage selected by the url scheme
+/// `memory:` keeps everything in memory (dry runs), anything else is a `LogsDB`.
+///
+/// # Errors
+/// Will return `Err` if `LogsDB::connect` fails.
+pub async fn connect(url: &str) -> Result<Arc<dyn Storage>> {
+    if url.starts_with("memory:") {
+        return Ok(Arc::new(MemoryDB::default()));
+    }
+
+    Ok(Arc::new(LogsDB::connect(url).await?))
+}
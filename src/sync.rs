@@ -0,0 +1,623 @@
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
+
+use color_eyre::{
+    eyre::{bail, eyre},
+    Report,
+    Result,
+};
+use poise::serenity_prelude::Http;
+use time::OffsetDateTime;
+use tokio::{
+    sync::{mpsc, oneshot, Mutex, RwLock},
+    task::JoinHandle,
+    time::Instant,
+};
+use vrchatapi::models::GroupAuditLogEntry;
+
+use crate::{
+    config::Config,
+    feed::Feed,
+    logsdb::{Log, Quarantined},
+    storage::Storage,
+    vrchat::{VRChat, MAX},
+};
+
+/// Audit log ingestion statistics
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SyncStats {
+    pub new:         usize,
+    pub existing:    usize,
+    pub failed:      usize,
+    pub quarantined: usize,
+}
+
+impl fmt::Display for SyncStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} new, {} existing, {} failed, {} quarantined",
+            self.new, self.existing, self.failed, self.quarantined
+        )
+    }
+}
+
+impl std::ops::AddAssign for SyncStats {
+    fn add_assign(&mut self, other: Self) {
+        self.new += other.new;
+        self.existing += other.existing;
+        self.failed += other.failed;
+        self.quarantined += other.quarantined;
+    }
+}
+
+/// Exponential backoff with jitter
+#[derive(Clone, Copy, Debug)]
+pub struct Backoff {
+    pub failures: u32,
+    base:         Duration,
+    max:          Duration,
+}
+
+impl Backoff {
+    #[must_use]
+    pub const fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            failures: 0,
+            base,
+            max,
+        }
+    }
+
+    /// # Record a failure and get the delay before the next attempt
+    /// The delay doubles with each consecutive failure up to the maximum,
+    /// then up to half of it is randomly shaved off to spread out retries.
+    pub fn fail(&mut self) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+
+        let factor = 2u32.saturating_pow(self.failures - 1);
+        let delay = self.base.saturating_mul(factor).min(self.max);
+        let jitter = delay.mul_f64(fastrand::f64() / 2.0);
+
+        delay.saturating_sub(jitter)
+    }
+
+    /// # Record a success and reset the consecutive failures
+    pub const fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// What to do when syncing the audit logs
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncMode {
+    /// Sync the newest audit logs until a known entry is reached
+    Incremental,
+    /// Walk the audit log history between the start and end dates (or all of it)
+    Backfill {
+        start: Option<OffsetDateTime>,
+        end:   Option<OffsetDateTime>,
+    },
+}
+
+/// Messages sent from the `SyncHandle` to the sync task
+pub enum SyncCommand {
+    Run(SyncMode, Option<String>, oneshot::Sender<Result<SyncStats>>),
+    Trigger(Option<String>),
+    Stop,
+}
+
+/// Groups with a queued incremental sync (`None` is every group)
+type Pending = Arc<std::sync::Mutex<HashSet<Option<String>>>>;
+
+/// Sync task status
+/// The last sync, error and backoff are of the scheduled sync of every group.
+#[derive(Clone, Debug, Default)]
+pub struct SyncStatus {
+    pub last_sync:  Option<OffsetDateTime>,
+    pub last_stats: SyncStats,
+    pub total:      SyncStats,
+    pub last_error: Option<String>,
+    pub failures:   u32,
+    pub backoff:    Option<Duration>,
+    pub degraded:   bool,
+    pub restarts:   u32,
+}
+
+/// Handle to the supervised audit log sync task
+#[derive(Clone)]
+pub struct SyncHandle {
+    sender:  mpsc::UnboundedSender<SyncCommand>,
+    status:  Arc<RwLock<SyncStatus>>,
+    pending: Pending,
+    task:    Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+/// State shared between the supervisor and the sync task across restarts
+struct Syncer {
+    http:     Arc<Http>,
+    config:   Config,
+    logsdb:   Arc<dyn Storage>,
+    vrchat:   Arc<VRChat>,
+    status:   Arc<RwLock<SyncStatus>>,
+    pending:  Pending,
+    receiver: Mutex<mpsc::UnboundedReceiver<SyncCommand>>,
+}
+
+impl SyncHandle {
+    /// # Spawn the supervised sync task
+    /// The task is restarted if it panics and runs until `stop` is called.
+    #[must_use]
+    pub fn spawn(
+        http: Arc<Http>,
+        config: Config,
+        logsdb: Arc<dyn Storage>,
+        vrchat: Arc<VRChat>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let pending = Pending::default();
+
+        let syncer = Arc::new(Syncer {
+            http,
+            config,
+            logsdb,
+            vrchat,
+            status: status.clone(),
+            pending: pending.clone(),
+            receiver: Mutex::new(receiver),
+        });
+
+        let task = tokio::spawn(supervise(syncer));
+
+        Self {
+            sender,
+            status,
+            pending,
+            task: Arc::new(Mutex::new(Some(task))),
+        }
+    }
+
+    /// # Get the current sync status
+    pub async fn status(&self) -> SyncStatus {
+        self.status.read().await.clone()
+    }
+
+    /// # Run a sync immediately and wait for it to finish
+    /// Syncs every group if no group id is given.
+    /// Backfills run beside the other syncs, only one at a time.
+    ///
+    /// # Errors
+    /// Will return `Err` if the sync task is stopped, a backfill is already running
+    /// or the sync fails.
+    pub async fn run(&self, mode: SyncMode, group_id: Option<&str>) -> Result<SyncStats> {
+        let (sender, receiver) = oneshot::channel();
+        let group_id = group_id.map(ToOwned::to_owned);
+        self.sender
+            .send(SyncCommand::Run(mode, group_id, sender))
+            .map_err(|_| eyre!("Sync task is stopped"))?;
+
+        receiver.await.map_err(|_| eyre!("Sync task is stopped"))?
+    }
+
+    /// # Queue an incremental sync without waiting for it to finish
+    /// Syncs every group if no group id is given.
+    /// Triggers for a group that is already queued are coalesced into one sync.
+    ///
+    /// # Errors
+    /// Will return `Err` if the sync task is stopped.
+    ///
+    /// # Panics
+    /// Will panic if `Mutex::lock` fails.
+    pub fn trigger(&self, group_id: Option<&str>) -> Result<()> {
+        let group_id = group_id.map(ToOwned::to_owned);
+        let queued = self
+            .pending
+            .lock()
+            .expect("Failed to lock")
+            .insert(group_id.clone());
+
+        if !queued {
+            return Ok(()); // The group is already queued
+        }
+
+        self.sender
+            .send(SyncCommand::Trigger(group_id))
+            .map_err(|_| eyre!("Sync task is stopped"))
+    }
+
+    /// # Stop the sync task and wait for it to finish
+    pub async fn stop(&self) {
+        let _ = self.sender.send(SyncCommand::Stop);
+        let task = self.task.lock().await.take();
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+    }
+}
+
+/// # Run the sync task, restarting it if it panics
+async fn supervise(syncer: Arc<Syncer>) {
+    let mut startup = true;
+
+    loop {
+        let task = tokio::spawn(work(syncer.clone(), startup));
+        match task.await {
+            Err(error) if error.is_panic() => {
+                eprintln!("Error: Sync task panicked, restarting: {error}");
+                syncer.status.write().await.restarts += 1;
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            _ => break, // The sync task was stopped
+        }
+
+        startup = false;
+    }
+}
+
+/// # Sync the groups audit logs on an interval
+/// Failures are retried with exponential backoff and jitter, once
+/// `sync_max_failures` is reached the sync is degraded and retried every `sync_max_backoff`.
+/// Triggered and on-demand syncs run in between, they don't move the schedule or the backoff.
+/// Backfills run beside the other syncs, one at a time.
+#[allow(clippy::significant_drop_tightening)] // The receiver is held for the life of the task
+async fn work(syncer: Arc<Syncer>, startup: bool) {
+    let Syncer {
+        config,
+        status,
+        pending,
+        receiver,
+        ..
+    } = syncer.as_ref();
+
+    let max_backoff = Duration::from_secs(config.sync_max_backoff);
+    let mut backoff = Backoff::new(Duration::from_secs(30), max_backoff);
+    let mut receiver = receiver.lock().await;
+
+    let mut backfill: Option<JoinHandle<()>> = None;
+    let mut next_sync = Instant::now();
+    let mut mode = if startup && config.backfill_on_startup {
+        SyncMode::Backfill {
+            start: None,
+            end:   None,
+        }
+    } else {
+        SyncMode::Incremental
+    };
+
+    loop {
+        let command = tokio::select! {
+            () = tokio::time::sleep_until(next_sync) => None,
+            command = receiver.recv() => Some(command),
+        };
+
+        match command {
+            None => {
+                let result = sync_groups(&syncer, mode, None).await;
+                next_sync = Instant::now() + record(&syncer, &mut backoff, &result).await;
+                mode = SyncMode::Incremental;
+            }
+            Some(Some(SyncCommand::Run(mode @ SyncMode::Backfill { .. }, group_id, reply))) => {
+                if backfill.as_ref().is_some_and(|task| !task.is_finished()) {
+                    let _ = reply.send(Err(eyre!("A backfill is already running")));
+                    continue;
+                }
+
+                /* Walking the whole history would hold up the scheduled and triggered syncs */
+                let syncer = syncer.clone();
+                let task = tokio::spawn(async move { run(&syncer, mode, group_id, reply).await });
+                backfill = Some(task);
+            }
+            Some(Some(SyncCommand::Run(mode, group_id, reply))) => {
+                run(&syncer, mode, group_id, reply).await;
+            }
+            Some(Some(SyncCommand::Trigger(group_id))) => {
+                /* Unqueue before syncing so events during the sync queue another */
+                pending.lock().expect("Failed to lock").remove(&group_id);
+                match sync_groups(&syncer, SyncMode::Incremental, group_id.as_deref()).await {
+                    Ok(stats) => {
+                        println!("Synced triggered audit logs: {stats}");
+                        status.write().await.total += stats;
+                    }
+                    Err(error) => eprintln!("Error: Failed to sync triggered audit logs: {error}"),
+                }
+            }
+            Some(Some(SyncCommand::Stop) | None) => break,
+        }
+    }
+
+    if let Some(task) = backfill {
+        task.abort();
+    }
+}
+
+/// # Run an on-demand sync and reply with the result
+async fn run(
+    syncer: &Syncer,
+    mode: SyncMode,
+    group_id: Option<String>,
+    reply: oneshot::Sender<Result<SyncStats>>,
+) {
+    let result = sync_groups(syncer, mode, group_id.as_deref()).await;
+    if let Ok(stats) = &result {
+        syncer.status.write().await.total += *stats;
+    }
+
+    let _ = reply.send(result);
+}
+
+/// # Record the result of a scheduled sync in the status
+/// Returns the delay before the next scheduled sync.
+async fn record(syncer: &Syncer, backoff: &mut Backoff, result: &Result<SyncStats>) -> Duration {
+    let Syncer { config, status, .. } = syncer;
+
+    let interval = Duration::from_secs(config.sync_interval);
+    let max_backoff = Duration::from_secs(config.sync_max_backoff);
+    let mut current = status.write().await;
+    let delay = match result {
+        Ok(stats) => {
+            println!("Synced audit logs: {stats}");
+            backoff.reset();
+            current.last_sync = Some(OffsetDateTime::now_utc());
+            current.last_stats = *stats;
+            current.total += *stats;
+            current.last_error = None;
+
+            interval
+        }
+        Err(error) => {
+            let delay = backoff.fail();
+            current.last_error = Some(error.to_string());
+
+            if backoff.failures >= config.sync_max_failures {
+                eprintln!(
+                    "Error: Sync degraded after {} failures, retrying every {}s: {error}",
+                    backoff.failures,
+                    max_backoff.as_secs()
+                );
+
+                max_backoff
+            } else {
+                eprintln!(
+                    "Error: Failed to sync audit logs ({}/{}), retrying in {}s: {error}",
+                    backoff.failures,
+                    config.sync_max_failures,
+                    delay.as_secs()
+                );
+
+                delay
+            }
+        }
+    };
+
+    current.failures = backoff.failures;
+    current.backoff = Some(delay).filter(|_| backoff.failures > 0);
+    current.degraded = backoff.failures >= config.sync_max_failures;
+
+    delay
+}
+
+/// # Sync the groups newest audit logs
+/// Only fetches audit logs created since the newest log in the database,
+/// walking them page by page (newest to oldest) until a page contains
+/// entries that are already in the database.
+/// Returns the statistics and the logs that were new (newest first), none
+/// on the first sync so the groups whole history isn't posted to the feed.
+///
+/// # Errors
+/// Will return `Err` if `get_latest_created_at` or `get_group_audit_logs_between` fails.
+pub async fn sync(
+    logsdb: &dyn Storage,
+    vrchat: &VRChat,
+    group_id: &str,
+) -> Result<(SyncStats, Vec<Log>)> {
+    let start = logsdb.get_latest_created_at(group_id).await?;
+
+    walk(logsdb, vrchat, group_id, start, None, SyncMode::Incremental).await
+}
+
+/// # Backfill the groups audit logs
+/// Walks the audit logs created between the start and end dates page by page
+/// (newest to oldest) until the oldest entry is reached, filling in any gaps.
+///
+/// # Errors
+/// Will return `Err` if `get_group_audit_logs_between` fails.
+pub async fn backfill(
+    logsdb: &dyn Storage,
+    vrchat: &VRChat,
+    group_id: &str,
+    start: Option<OffsetDateTime>,
+    end: Option<OffsetDateTime>,
+) -> Result<SyncStats> {
+    let mode = SyncMode::Backfill { start, end };
+    let (stats, _) = walk(logsdb, vrchat, group_id, start, end, mode).await?;
+
+    Ok(stats)
+}
+
+/// # Insert the logs into the database and quarantine the malformed entries
+/// Duplicates and entries that are already quarantined are counted as existing,
+/// failures are logged and counted.
+/// Returns the statistics and the logs that were new.
+pub async fn ingest(
+    logsdb: &dyn Storage,
+    logs: Vec<Log>,
+    quarantined: &[Quarantined],
+) -> (SyncStats, Vec<Log>) {
+    let mut stats = SyncStats::default();
+    let mut new = Vec::new();
+
+    for log in logs {
+        match logsdb.insert_log(&log).await {
+            Ok(true) => {
+                stats.new += 1;
+                new.push(log);
+            }
+            Ok(false) => stats.existing += 1,
+            Err(error) => {
+                eprintln!("Error: {error}");
+                stats.failed += 1;
+            }
+        }
+    }
+
+    for entry in quarantined {
+        match logsdb.insert_quarantined(entry).await {
+            Ok(true) => {
+                eprintln!("Error: Quarantined audit log {}: {}", entry.id, entry.error);
+                stats.quarantined += 1;
+            }
+            Ok(false) => stats.existing += 1,
+            Err(error) => {
+                eprintln!("Error: {error}");
+                stats.failed += 1;
+            }
+        }
+    }
+
+    (stats, new)
+}
+
+/// # Re-process the quarantined audit log entries
+/// Entries that now convert into a `Log` are inserted and removed from quarantine.
+///
+/// # Errors
+/// Will return `Err` if `get_quarantined` fails.
+pub async fn reprocess(logsdb: &dyn Storage) -> Result<SyncStats> {
+    let mut stats = SyncStats::default();
+
+    for quarantined in logsdb.get_quarantined().await? {
+        let log = serde_json::from_value::<GroupAuditLogEntry>(quarantined.entry.clone())
+            .map_err(Report::msg)
+            .and_then(Log::try_from);
+
+        let log = match log {
+            Ok(log) => log,
+            Err(error) => {
+                let quarantined = Quarantined {
+                    error: error.to_string(),
+                    ..quarantined
+                };
+                if let Err(error) = logsdb.insert_quarantined(&quarantined).await {
+                    eprintln!("Error: {error}");
+                }
+
+                stats.quarantined += 1;
+                continue;
+            }
+        };
+
+        match logsdb.insert_log(&log).await {
+            Ok(new) => {
+                if new {
+                    stats.new += 1;
+                } else {
+                    stats.existing += 1;
+                }
+
+                logsdb.remove_quarantined(&quarantined.id).await?;
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                stats.failed += 1;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+async fn walk(
+    logsdb: &dyn Storage,
+    vrchat: &VRChat,
+    group_id: &str,
+    start: Option<OffsetDateTime>,
+    end: Option<OffsetDateTime>,
+    mode: SyncMode,
+) -> Result<(SyncStats, Vec<Log>)> {
+    let mut stats = SyncStats::default();
+    let mut new = Vec::new();
+    let mut offset = 0;
+
+    /* Only collect the new logs for the feed when there was a previous sync */
+    let collect = mode == SyncMode::Incremental && start.is_some();
+
+    loop {
+        let (logs, quarantined) = vrchat
+            .get_group_audit_logs_between(group_id, MAX, offset, start, end)
+            .await?;
+
+        if logs.is_empty() && quarantined.is_empty() {
+            break; // There are no older logs
+        }
+
+        let (page, logs) = ingest(logsdb, logs, &quarantined).await;
+        stats += page;
+
+        if collect {
+            new.extend(logs);
+        }
+
+        if mode == SyncMode::Incremental && page.existing > 0 {
+            break; // The rest of the logs are already stored
+        }
+
+        offset += MAX;
+
+        /* Go easy on the VRChat API between pages */
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    Ok((stats, new))
+}
+
+/// # Sync the audit logs of every group (or only the given group)
+/// Every group is synced even if some fail, then the errors are combined.
+async fn sync_groups(syncer: &Syncer, mode: SyncMode, group_id: Option<&str>) -> Result<SyncStats> {
+    let Syncer {
+        http,
+        config,
+        logsdb,
+        vrchat,
+        ..
+    } = syncer;
+
+    let mut stats = SyncStats::default();
+    let mut errors = Vec::new();
+    let groups = config
+        .vrc_groups
+        .iter()
+        .filter(|group| group_id.is_none_or(|group_id| group.group_id == group_id));
+
+    for group in groups {
+        let group_id = &group.group_id;
+        let result = match mode {
+            SyncMode::Incremental => match sync(logsdb.as_ref(), vrchat, group_id).await {
+                Ok((stats, logs)) => {
+                    if let Some(channel_id) = group.log_channel_id {
+                        let feed = Feed::new(channel_id, group.log_event_types.clone());
+                        if let Err(error) = feed.post(http, &logs).await {
+                            eprintln!("Error: Failed to post {group_id} audit logs: {error}");
+                        }
+                    }
+
+                    Ok(stats)
+                }
+                Err(error) => Err(error),
+            },
+            SyncMode::Backfill { start, end } => {
+                backfill(logsdb.as_ref(), vrchat, group_id, start, end).await
+            }
+        };
+
+        match result {
+            Ok(group_stats) => stats += group_stats,
+            Err(error) => errors.push(format!("{group_id}: {error}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!(errors.join(", "));
+    }
+
+    Ok(stats)
+}
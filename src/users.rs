@@ -0,0 +1,69 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::Result;
+use time::OffsetDateTime;
+
+use crate::{config::Config, logsdb::CachedUser, storage::Storage, vrchat::VRChat};
+
+/// # Get a user profile from the cache first
+/// Users that aren't cached yet are fetched and cached.
+///
+/// # Errors
+/// Will return `Err` if the storage or `VRChat::get_user` fails.
+pub async fn get_user(logsdb: &dyn Storage, vrchat: &VRChat, user_id: &str) -> Result<CachedUser> {
+    if let Some(user) = logsdb.get_user(user_id).await? {
+        return Ok(user);
+    }
+
+    let user = CachedUser::from(vrchat.get_user(user_id).await?);
+    logsdb.upsert_user(&user).await?;
+
+    Ok(user)
+}
+
+/// # Refresh up to `batch` cached users that are older than the TTL
+/// Returns the number of users refreshed.
+///
+/// # Errors
+/// Will return `Err` if the storage fails.
+pub async fn refresh(
+    logsdb: &dyn Storage,
+    vrchat: &VRChat,
+    ttl: Duration,
+    batch: u32,
+) -> Result<usize> {
+    let now = OffsetDateTime::now_utc();
+    let stale = logsdb.get_stale_users(now - ttl, i64::from(batch)).await?;
+    let mut count = 0;
+
+    for mut user in stale {
+        match vrchat.get_user(&user.id).await {
+            Ok(fetched) => {
+                user = CachedUser::from(fetched);
+                count += 1;
+            }
+            Err(error) => {
+                /* Keep the stale profile until the next TTL instead of retrying every batch */
+                eprintln!("Error: Refresh user {}: {error}", user.id);
+                user.updated_at = now;
+            }
+        }
+
+        logsdb.upsert_user(&user).await?;
+    }
+
+    Ok(count)
+}
+
+/// # Refresh the cached users on the configured interval
+pub async fn run(config: Config, logsdb: Arc<dyn Storage>, vrchat: Arc<VRChat>) {
+    let ttl = Duration::from_secs(config.user_cache_ttl);
+    let mut interval = tokio::time::interval(Duration::from_secs(config.user_refresh_interval));
+    loop {
+        interval.tick().await;
+        let batch = config.user_refresh_batch;
+        if let Err(error) = refresh(logsdb.as_ref(), &vrchat, ttl, batch).await {
+            eprintln!("Error: User refresh: {error}");
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use color_eyre::{eyre::eyre, Result};
 use reqwest::{Client, Url};
 use reqwest_cookie_store::CookieStoreRwLock;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use totp::{Algorithm, Secret, TOTP};
 use vrchatapi::{
     apis::{
@@ -11,7 +12,6 @@ use vrchatapi::{
         groups_api::{
             self,
             BanGroupMemberError,
-            GetGroupAuditLogsError,
             GetGroupMemberError,
             UnbanGroupMemberError,
         },
@@ -29,7 +29,7 @@ use vrchatapi::{
     },
 };
 
-use crate::logsdb::Log;
+use crate::logsdb::{Log, Quarantined};
 
 pub const MAX: i32 = 100;
 
@@ -118,37 +118,64 @@ impl VRChat {
             .collect()
     }
 
+    /// # Get the auth token from the cookie store
+    ///
+    /// # Panics
+    /// Will panic if `RwLock::read` fails.
+    #[must_use]
+    pub fn get_auth_token(&self) -> Option<String> {
+        self.cookie
+            .read()
+            .unwrap()
+            .iter_any()
+            .find(|cookie| cookie.name() == "auth")
+            .map(|cookie| cookie.value().to_owned())
+    }
+
+    /// # Get the user agent
+    #[must_use]
+    pub fn get_user_agent(&self) -> Option<&str> {
+        self.config.user_agent.as_deref()
+    }
+
     /* Groups API */
 
-    /// # Get the groups audit logs
+    /// # Get the groups audit logs created between the start and end dates
+    /// Entries that fail to convert into a `Log` are returned as `Quarantined`.
     ///
     /// # Errors
-    /// Will return `Err` if `get_group_audit_logs` fails.
-    pub async fn get_group_audit_logs(
+    /// Will return `Err` if a date fails to format or `get_group_audit_logs` fails.
+    pub async fn get_group_audit_logs_between(
         &self,
         group_id: &str,
         number: i32,
         offset: i32,
-    ) -> Result<Vec<Log>, Error<GetGroupAuditLogsError>> {
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+    ) -> Result<(Vec<Log>, Vec<Quarantined>)> {
+        let start = start.map(|date| date.format(&Rfc3339)).transpose()?;
+        let end = end.map(|date| date.format(&Rfc3339)).transpose()?;
         let audit_logs = groups_api::get_group_audit_logs(
             &self.config,
             group_id,
             Some(number),
             Some(offset),
-            None,
-            None,
+            start,
+            end,
         )
         .await?;
 
-        let logs = audit_logs
-            .results
-            .into_iter()
-            .flatten()
-            .map(Log::try_from)
-            .filter_map(Result::ok)
-            .collect();
+        let mut logs = Vec::new();
+        let mut quarantined = Vec::new();
+        for entry in audit_logs.results.into_iter().flatten() {
+            let raw = serde_json::to_value(&entry)?;
+            match Log::try_from(entry) {
+                Ok(log) => logs.push(log),
+                Err(error) => quarantined.push(Quarantined::new(group_id, raw, &error)),
+            }
+        }
 
-        Ok(logs)
+        Ok((logs, quarantined))
     }
 
     /// # Get a group member
@@ -0,0 +1,94 @@
+/* Every test crate only uses some of the helpers */
+#![allow(dead_code)]
+
+use std::env;
+
+use serde_json::json;
+use time::OffsetDateTime;
+use tokio::sync::{Mutex, MutexGuard};
+use vrc_ban::{
+    event::EventType,
+    logsdb::{Log, LogsDB},
+    memorydb::MemoryDB,
+    storage::Storage,
+};
+
+/// Tests in a crate run in parallel but share the database servers
+static SERVERS: Mutex<()> = Mutex::const_new(());
+
+/// Empty storage backends to run a test against
+/// `MemoryDB` and in-memory `SQLite` always run, `MySQL` and `PostgreSQL` only when
+/// `TEST_MYSQL_URL` or `TEST_POSTGRES_URL` point at a database the tests may empty.
+pub struct Backends {
+    _lock:    MutexGuard<'static, ()>,
+    backends: Vec<(&'static str, Box<dyn Storage>)>,
+}
+
+impl Backends {
+    pub async fn new() -> Self {
+        let lock = SERVERS.lock().await;
+        let mut backends: Vec<(&'static str, Box<dyn Storage>)> =
+            vec![("memory", Box::new(MemoryDB::default()))];
+
+        #[cfg(feature = "sqlite")]
+        backends.push(("sqlite", Box::new(connect("sqlite::memory:").await)));
+
+        if let Ok(url) = env::var("TEST_MYSQL_URL") {
+            backends.push(("mysql", Box::new(connect(&url).await)));
+        }
+
+        #[cfg(feature = "postgres")]
+        if let Ok(url) = env::var("TEST_POSTGRES_URL") {
+            backends.push(("postgres", Box::new(connect(&url).await)));
+        }
+
+        Self {
+            _lock: lock,
+            backends,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &dyn Storage)> {
+        self.backends
+            .iter()
+            .map(|(name, logsdb)| (*name, logsdb.as_ref()))
+    }
+}
+
+/// # Connect to the database and empty every table
+pub async fn connect(url: &str) -> LogsDB {
+    let logsdb = LogsDB::connect(url).await.unwrap();
+    for table in ["logs", "quarantine", "users"] {
+        let statement = format!("DELETE FROM {table}");
+        match &logsdb {
+            LogsDB::MySql(pool) => sqlx::query(&statement).execute(pool).await.map(|_| ()),
+            #[cfg(feature = "sqlite")]
+            LogsDB::Sqlite(pool) => sqlx::query(&statement).execute(pool).await.map(|_| ()),
+            #[cfg(feature = "postgres")]
+            LogsDB::Postgres(pool) => sqlx::query(&statement).execute(pool).await.map(|_| ()),
+        }
+        .unwrap();
+    }
+
+    logsdb
+}
+
+/// # Create a log of `usr_staff` in `grp_1`
+pub fn log(id: &str, created_at: OffsetDateTime, event_type: EventType, target_id: &str) -> Log {
+    Log {
+        id: id.to_owned(),
+        created_at,
+        group_id: "grp_1".to_owned(),
+        actor_id: "usr_staff".to_owned(),
+        actor_display_name: Some("Staff".to_owned()),
+        target_id: Some(target_id.to_owned()),
+        event_type,
+        description: format!("{id} description"),
+        data: json!({ "id": id }),
+    }
+}
+
+/// # Get the ids of the logs
+pub fn ids(logs: &[Log]) -> Vec<&str> {
+    logs.iter().map(|log| log.id.as_str()).collect()
+}
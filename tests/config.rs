@@ -0,0 +1,40 @@
+use serde_json::{json, Value};
+use vrc_ban::config::Config;
+
+/// # Parse a config with the required secrets and the extra fields
+fn parse(extra: &Value) -> serde_json::Result<Config> {
+    let mut config = json!({
+        "bot_secret": "bot",
+        "sql_secret": "mysql://localhost/vrc",
+        "vrc_secret": "",
+        "vrc_password": "password",
+        "vrc_username": "username",
+    });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+
+    serde_json::from_value(config)
+}
+
+#[test]
+fn sync_interval_has_a_minimum() {
+    assert_eq!(parse(&json!({})).unwrap().sync_interval, 600);
+    assert_eq!(parse(&json!({ "sync_interval": 60 })).unwrap().sync_interval, 60);
+    assert!(parse(&json!({ "sync_interval": 0 })).is_err());
+    assert!(parse(&json!({ "sync_interval": 59 })).is_err());
+}
+
+#[test]
+fn purge_interval_is_not_zero() {
+    assert_eq!(parse(&json!({ "purge_interval": 1 })).unwrap().purge_interval, 1);
+    assert!(parse(&json!({ "purge_interval": 0 })).is_err());
+}
+
+#[test]
+fn user_refresh_interval_is_not_zero() {
+    let config = parse(&json!({ "user_refresh_interval": 1 })).unwrap();
+    assert_eq!(config.user_refresh_interval, 1);
+    assert!(parse(&json!({ "user_refresh_interval": 0 })).is_err());
+}
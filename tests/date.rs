@@ -0,0 +1,22 @@
+use time::macros::datetime;
+use vrc_ban::date::{parse_date, parse_end_date};
+
+#[test]
+fn dates_are_midnight_utc() {
+    assert_eq!(parse_date("2024-03-31").unwrap(), datetime!(2024-03-31 0:00 UTC));
+    assert_eq!(
+        parse_date("2024-03-31T12:00:00+02:00").unwrap(),
+        datetime!(2024-03-31 12:00 +2),
+    );
+    assert!(parse_date("31/03/2024").is_err());
+}
+
+#[test]
+fn end_dates_include_the_whole_day() {
+    assert_eq!(parse_end_date("2024-03-31").unwrap(), datetime!(2024-04-01 0:00 UTC));
+    assert_eq!(
+        parse_end_date("2024-03-31T12:00:00Z").unwrap(),
+        datetime!(2024-03-31 12:00 UTC),
+    );
+    assert!(parse_end_date("9999-12-31").is_err());
+}
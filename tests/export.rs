@@ -0,0 +1,84 @@
+mod common;
+
+use common::{log, Backends};
+use serde_json::Value;
+use time::{macros::datetime, Duration};
+use vrc_ban::{
+    event::EventType,
+    export::{self, ExportFormat},
+    logsdb::Log,
+    storage::LogFilter,
+};
+
+/// # Get the logs with a quoted description and an unknown event type without a target
+fn fixtures() -> [Log; 2] {
+    let at = datetime!(2024-01-01 12:30:15.250 UTC);
+    let quoted = Log {
+        description: String::from("Said \"hi\",\nthen left"),
+        ..log("a", at, EventType::Ban, "usr_1")
+    };
+    let unknown = Log {
+        actor_display_name: None,
+        target_id: None,
+        ..log("b", at + Duration::days(1), "group.future.event".into(), "usr_2")
+    };
+
+    [quoted, unknown]
+}
+
+#[tokio::test]
+async fn csv_quotes_the_delimiters() {
+    let expected = [
+        "id,created_at,group_id,actor_id,actor_display_name,target_id,event_type,description,data",
+        "a,2024-01-01T12:30:15.25Z,grp_1,usr_staff,Staff,usr_1,group.user.ban,\
+         \"Said \"\"hi\"\",\nthen left\",\"{\"\"id\"\":\"\"a\"\"}\"",
+        "b,2024-01-02T12:30:15.25Z,grp_1,usr_staff,,,group.future.event,\
+         b description,\"{\"\"id\"\":\"\"b\"\"}\"",
+        "",
+    ];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &fixtures() {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let filter = LogFilter::default();
+        let (output, count) = export::export(logsdb, &filter, ExportFormat::Csv, Vec::new())
+            .await
+            .unwrap();
+
+        assert_eq!(count, 2, "{name}");
+        assert_eq!(String::from_utf8(output).unwrap(), expected.join("\n"), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn json_and_ndjson_hold_every_field() {
+    let expected = fixtures()
+        .iter()
+        .map(|log| serde_json::to_value(log).unwrap())
+        .collect::<Vec<_>>();
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &fixtures() {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let filter = LogFilter::default();
+        let (output, _) = export::export(logsdb, &filter, ExportFormat::Json, Vec::new())
+            .await
+            .unwrap();
+        let json: Vec<Value> = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json, expected, "{name}");
+
+        let (output, _) = export::export(logsdb, &filter, ExportFormat::Ndjson, Vec::new())
+            .await
+            .unwrap();
+        let ndjson = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect::<Vec<Value>>();
+        assert_eq!(ndjson, expected, "{name}");
+    }
+}
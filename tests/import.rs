@@ -0,0 +1,66 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::{log, Backends};
+use serde_json::Value;
+use time::{macros::datetime, Duration};
+use vrc_ban::{
+    event::EventType,
+    export::{self, ExportFormat},
+    import,
+    logsdb::Log,
+    memorydb::MemoryDB,
+    storage::{LogFilter, Storage},
+};
+
+/// # Collect every log as JSON
+async fn all_logs(logsdb: &dyn Storage) -> Vec<Value> {
+    let mut logs = Vec::new();
+    logsdb
+        .for_each_log(&LogFilter::default(), &mut |log| {
+            logs.push(serde_json::to_value(log)?);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    logs
+}
+
+#[tokio::test]
+async fn export_then_import_roundtrips() {
+    let at = datetime!(2024-01-01 12:30:15.250 UTC);
+    let quoted = Log {
+        description: String::from("Said \"hi\",\nthen left"),
+        ..log("a", at, EventType::Ban, "usr_1")
+    };
+    let unknown = Log {
+        actor_display_name: None,
+        target_id: None,
+        ..log("b", at + Duration::days(1), "group.future.event".into(), "usr_2")
+    };
+
+    let source = MemoryDB::default();
+    for log in [&quoted, &unknown] {
+        source.insert_log(log).await.unwrap();
+    }
+    let expected = all_logs(&source).await;
+
+    for format in [ExportFormat::Json, ExportFormat::Ndjson] {
+        let filter = LogFilter::default();
+        let (output, count) = export::export(&source, &filter, format, Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(count, 2, "{format:?}");
+
+        for (name, logsdb) in Backends::new().await.iter() {
+            let stats = import::import(logsdb, Cursor::new(&output)).await.unwrap();
+            assert_eq!((stats.imported, stats.duplicates, stats.invalid), (2, 0, 0), "{name}");
+            assert_eq!(all_logs(logsdb).await, expected, "{name} {format:?}");
+
+            let stats = import::import(logsdb, Cursor::new(&output)).await.unwrap();
+            assert_eq!((stats.imported, stats.duplicates), (0, 2), "{name} {format:?}");
+        }
+    }
+}
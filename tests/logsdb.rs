@@ -0,0 +1,312 @@
+mod common;
+
+use color_eyre::eyre::eyre;
+use common::{ids, log, Backends};
+use serde_json::json;
+use time::{macros::datetime, Duration};
+use vrc_ban::{
+    event::EventType,
+    logsdb::{CachedUser, Log, Quarantined},
+    storage::{LogFilter, Storage},
+};
+
+/// # Collect every log matching the filter
+async fn filtered(logsdb: &dyn Storage, filter: &LogFilter) -> Vec<Log> {
+    let mut logs = Vec::new();
+    logsdb
+        .for_each_log(filter, &mut |log| {
+            logs.push(log);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    logs
+}
+
+#[tokio::test]
+async fn logs_roundtrip_and_filter() {
+    let at = datetime!(2024-01-01 12:30:15.250 UTC);
+    let mut unknown = log("b", at + Duration::days(1), "group.future.event".into(), "usr_2");
+    unknown.actor_display_name = None;
+    unknown.target_id = None;
+    unknown.data = json!({ "nested": { "list": [1, "two"] } });
+    let logs = [log("a", at, EventType::Ban, "usr_1"), unknown];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        assert_eq!(logsdb.get_latest_created_at("grp_1").await.unwrap(), None, "{name}");
+        for log in &logs {
+            assert!(logsdb.insert_log(log).await.unwrap(), "{name}");
+        }
+
+        let all = filtered(logsdb, &LogFilter::default()).await;
+        assert_eq!(ids(&all), ["a", "b"], "{name}");
+        assert_eq!(all[0].created_at, at, "{name}");
+        assert_eq!(all[1].event_type, logs[1].event_type, "{name}");
+        assert_eq!(all[1].target_id, None, "{name}");
+        assert_eq!(all[1].data, logs[1].data, "{name}");
+
+        let filter = LogFilter {
+            event_type: Some(EventType::Ban),
+            target_id: Some("usr_1".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&filtered(logsdb, &filter).await), ["a"], "{name}");
+
+        let filter = LogFilter {
+            start: Some(at + Duration::hours(1)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&filtered(logsdb, &filter).await), ["b"], "{name}");
+
+        let latest = logsdb.get_latest_created_at("grp_1").await.unwrap();
+        assert_eq!(latest, Some(at + Duration::days(1)), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn history_includes_joins_and_leaves() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let mut join = log("join", at, EventType::Join, "grp_1");
+    join.actor_id = "usr_1".to_owned();
+    let logs = [
+        join,
+        log("warn", at + Duration::days(1), EventType::InstanceWarn, "usr_1"),
+        log("ban", at + Duration::days(2), EventType::Ban, "usr_1"),
+        log("other", at + Duration::days(3), EventType::Ban, "usr_2"),
+    ];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let history = logsdb.get_history("grp_1", "usr_1").await.unwrap();
+        assert_eq!(ids(&history), ["ban", "warn", "join"], "{name}");
+    }
+}
+
+#[tokio::test]
+async fn actions_by_actor_filter_by_type_and_dates() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let mut other = log("other", at, EventType::Ban, "usr_3");
+    other.actor_id = "usr_other".to_owned();
+    let logs = [
+        log("a", at, EventType::Ban, "usr_1"),
+        log("b", at + Duration::days(1), EventType::Kick, "usr_2"),
+        log("c", at + Duration::days(2), EventType::Ban, "usr_2"),
+        other,
+    ];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let all = logsdb
+            .get_actions_by_actor("grp_1", "usr_staff", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&all), ["c", "b", "a"], "{name}");
+
+        let bans = logsdb
+            .get_actions_by_actor("grp_1", "usr_staff", Some(&EventType::Ban), None, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&bans), ["c", "a"], "{name}");
+
+        let (start, end) = (Some(at), Some(at + Duration::days(2)));
+        let ranged = logsdb
+            .get_actions_by_actor("grp_1", "usr_staff", None, start, end)
+            .await
+            .unwrap();
+        assert_eq!(ids(&ranged), ["b", "a"], "{name}");
+    }
+}
+
+#[tokio::test]
+async fn staff_stats_count_all_and_recent_actions() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let mut other = log("other", at, EventType::Kick, "usr_3");
+    other.actor_id = "usr_other".to_owned();
+    other.actor_display_name = None;
+    let logs = [
+        log("ban", at, EventType::Ban, "usr_1"),
+        log("kick", at, EventType::Kick, "usr_1"),
+        log("instance", at + Duration::days(10), EventType::InstanceKick, "usr_1"),
+        log("warn", at + Duration::days(10), EventType::InstanceWarn, "usr_2"),
+        log("join", at + Duration::days(10), EventType::Join, "grp_1"),
+        other,
+    ];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let recent = at + Duration::days(5);
+        let mut stats = logsdb.get_staff_stats("grp_1", recent).await.unwrap();
+        stats.sort_by(|a, b| a.actor_id.cmp(&b.actor_id));
+
+        let counts = stats
+            .iter()
+            .map(|stats| {
+                let all = (stats.all_bans, stats.all_kick, stats.all_warn);
+                let new = (stats.new_bans, stats.new_kick, stats.new_warn);
+                (stats.username.as_str(), all, new)
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            ("usr_other", (0, 1, 0), (0, 0, 0)),
+            ("Staff", (1, 2, 1), (0, 1, 1)),
+        ];
+        assert_eq!(counts, expected, "{name}");
+    }
+}
+
+#[tokio::test]
+async fn quarantine_upserts_the_error() {
+    let entry = json!({ "id": "gal_1", "eventType": 1 });
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        let quarantined = Quarantined::new("grp_1", entry.clone(), &eyre!("first"));
+        assert!(logsdb.insert_quarantined(&quarantined).await.unwrap(), "{name}");
+        let quarantined = Quarantined::new("grp_1", entry.clone(), &eyre!("second"));
+        assert!(!logsdb.insert_quarantined(&quarantined).await.unwrap(), "{name}");
+
+        assert_eq!(logsdb.count_quarantined().await.unwrap(), 1, "{name}");
+        let stored = logsdb.get_quarantined().await.unwrap();
+        assert_eq!(stored[0].id, "gal_1", "{name}");
+        assert_eq!(stored[0].entry, entry, "{name}");
+        assert_eq!(stored[0].error, "second", "{name}");
+
+        logsdb.remove_quarantined("gal_1").await.unwrap();
+        assert_eq!(logsdb.count_quarantined().await.unwrap(), 0, "{name}");
+    }
+}
+
+#[tokio::test]
+async fn users_upsert_and_go_stale() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let user = |id: &str, display_name: &str, updated_at| CachedUser {
+        id:           id.to_owned(),
+        display_name: display_name.to_owned(),
+        user_icon:    String::new(),
+        thumbnail:    format!("https://example.com/{id}.png"),
+        bio:          String::new(),
+        badges:       json!(["Supporter"]),
+        updated_at,
+    };
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        assert!(logsdb.get_user("usr_1").await.unwrap().is_none(), "{name}");
+        logsdb.upsert_user(&user("usr_1", "Old", at)).await.unwrap();
+        logsdb.upsert_user(&user("usr_2", "Two", at + Duration::days(1))).await.unwrap();
+        logsdb.upsert_user(&user("usr_3", "Three", at + Duration::days(9))).await.unwrap();
+        logsdb.upsert_user(&user("usr_1", "New", at + Duration::days(2))).await.unwrap();
+
+        let cached = logsdb.get_user("usr_1").await.unwrap().unwrap();
+        assert_eq!(cached.display_name, "New", "{name}");
+        assert_eq!(cached.badge_names(), ["Supporter"], "{name}");
+        assert_eq!(cached.updated_at, at + Duration::days(2), "{name}");
+
+        let stale = logsdb
+            .get_stale_users(at + Duration::days(5), 10)
+            .await
+            .unwrap();
+        let stale = stale.iter().map(|user| user.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(stale, ["usr_2", "usr_1"], "{name}");
+
+        let batch = logsdb
+            .get_stale_users(at + Duration::days(5), 1)
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 1, "{name}");
+    }
+}
+
+#[tokio::test]
+async fn latest_actions_are_deduplicated_and_keyset_paginated() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let logs = [
+        log("a", at, EventType::Ban, "usr_1"),
+        log("b", at + Duration::days(1), EventType::Unban, "usr_1"),
+        log("c", at + Duration::days(2), EventType::Ban, "usr_2"),
+        log("d", at + Duration::days(2), EventType::Ban, "usr_3"),
+        log("e", at + Duration::days(3), EventType::Kick, "usr_4"),
+    ];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        /* Only the latest ban or unban per target, ties broken by id */
+        let first = logsdb
+            .get_latest_actions("grp_1", None, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(ids(&first), ["d", "c"], "{name}");
+
+        let second = logsdb
+            .get_latest_actions("grp_1", None, first.last(), 2)
+            .await
+            .unwrap();
+        assert_eq!(ids(&second), ["b"], "{name}");
+
+        /* Nothing after the oldest action */
+        let last = logsdb
+            .get_latest_actions("grp_1", None, second.last(), 2)
+            .await
+            .unwrap();
+        assert!(last.is_empty(), "{name}");
+
+        let target = logsdb
+            .get_latest_actions("grp_1", Some("usr_1"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&target), ["b"], "{name}");
+    }
+}
+
+#[tokio::test]
+async fn search_matches_every_term() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+    let mut spam = log("a", at, EventType::Ban, "usr_1");
+    spam.description = "Banned for spam in the lobby".to_owned();
+    let mut other = log("b", at + Duration::days(1), EventType::Kick, "usr_2");
+    other.description = "Kicked for spam".to_owned();
+    other.data = json!({ "reason": "lobby" });
+    let logs = [spam, other, log("c", at + Duration::days(2), EventType::Ban, "usr_3")];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let found = logsdb
+            .search_logs("grp_1", "spam LOBBY", None, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&found), ["b", "a"], "{name}");
+
+        let bans = logsdb
+            .search_logs("grp_1", "spam", Some(&EventType::Ban), None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&bans), ["a"], "{name}");
+
+        let start = Some(at + Duration::days(1));
+        let ranged = logsdb
+            .search_logs("grp_1", "spam", None, start, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(ids(&ranged), ["b"], "{name}");
+
+        /* The query syntax characters are stripped, leaving nothing to search */
+        let empty = logsdb
+            .search_logs("grp_1", "\"*\" -", None, None, None, 10)
+            .await;
+        assert!(empty.is_err(), "{name}");
+    }
+}
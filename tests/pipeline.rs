@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::{http::header::USER_AGENT, Message};
+use vrc_ban::{config::Group, pipeline, sync::Backoff};
+
+#[test]
+fn request_has_token_and_user_agent() {
+    let request =
+        pipeline::request("wss://pipeline.test/", "authcookie_1", Some("vrc-ban")).unwrap();
+
+    assert_eq!(request.uri().query(), Some("authToken=authcookie_1"));
+    assert_eq!(request.headers()[USER_AGENT], "vrc-ban");
+}
+
+#[tokio::test]
+async fn group_events_trigger_a_sync() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/", listener.local_addr().unwrap());
+
+    /* Local stand-in for the VRChat pipeline */
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let messages = [
+            json!({
+                "type": "group-member-updated",
+                "content": r#"{"member":{"groupId":"grp_known"}}"#,
+            }),
+            json!({"type": "group-joined", "content": r#"{"groupId":"grp_other"}"#}),
+            json!({"type": "friend-online", "content": r#"{"userId":"usr_friend"}"#}),
+            json!({"type": "group-left", "content": "{}"}),
+        ];
+
+        socket
+            .send(Message::Text(String::from("not json")))
+            .await
+            .unwrap();
+        for message in messages {
+            socket
+                .send(Message::Text(message.to_string()))
+                .await
+                .unwrap();
+        }
+
+        socket.close(None).await.unwrap();
+        while socket.next().await.is_some() {}
+    });
+
+    let groups: Vec<Group> = serde_json::from_value(json!([{"group_id": "grp_known"}])).unwrap();
+    let mut backoff = Backoff::new(Duration::from_secs(5), Duration::from_secs(30));
+    backoff.fail();
+
+    let mut triggered = Vec::new();
+    let request = pipeline::request(&url, "authcookie_1", None).unwrap();
+    pipeline::listen(request, &groups, &mut backoff, &mut |group_id| {
+        triggered.push(group_id.map(ToOwned::to_owned));
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    server.await.unwrap();
+    assert_eq!(triggered, [Some(String::from("grp_known")), None]);
+    assert_eq!(backoff.failures, 0);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{log, Backends};
+use time::{Duration, OffsetDateTime};
+use vrc_ban::{
+    config::Retention,
+    event::EventType,
+    purge,
+    storage::{LogFilter, Storage},
+};
+
+/// # Count every log in the storage
+async fn count(logsdb: &dyn Storage) -> u64 {
+    let filter = LogFilter::default();
+    logsdb.for_each_log(&filter, &mut |_| Ok(())).await.unwrap()
+}
+
+#[tokio::test]
+async fn purge_dry_run_keeps_the_logs() {
+    let now = OffsetDateTime::now_utc();
+    let logs = [
+        log("old", now - Duration::days(60), EventType::Join, "grp_1"),
+        log("new", now - Duration::days(1), EventType::Join, "grp_1"),
+        log("ban", now - Duration::days(60), EventType::Ban, "usr_1"),
+    ];
+    let retention = [Retention {
+        event_type: EventType::Join,
+        days:       30,
+    }];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        for log in &logs {
+            logsdb.insert_log(log).await.unwrap();
+        }
+
+        let report = purge::purge(logsdb, &retention, true).await.unwrap();
+        assert!(report.dry_run, "{name}");
+        assert_eq!(report.purged, [(EventType::Join, 1)], "{name}");
+        assert_eq!(count(logsdb).await, 3, "{name}");
+
+        let report = purge::purge(logsdb, &retention, false).await.unwrap();
+        assert_eq!(report.total(), 1, "{name}");
+        assert_eq!(count(logsdb).await, 2, "{name}");
+    }
+}
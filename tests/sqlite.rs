@@ -0,0 +1,65 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use common::{ids, log};
+use sqlx::SqlitePool;
+use time::macros::datetime;
+use vrc_ban::{event::EventType, logsdb::LogsDB, storage::Storage};
+
+/// # Check that a whole second sorts before half a second past it
+async fn check_sub_second_order(logsdb: &LogsDB) {
+    let latest = logsdb.get_latest_created_at("grp_1").await.unwrap();
+    assert_eq!(latest, Some(datetime!(2024-01-01 0:00:00.5 UTC)));
+
+    let actions = logsdb
+        .get_latest_actions("grp_1", None, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(&actions), ["half", "whole"]);
+}
+
+#[tokio::test]
+async fn dates_sort_with_different_fraction_lengths() {
+    let logsdb = common::connect("sqlite::memory:").await;
+    let logs = [
+        log("whole", datetime!(2024-01-01 0:00 UTC), EventType::Ban, "usr_1"),
+        log("half", datetime!(2024-01-01 0:00:00.5 UTC), EventType::Ban, "usr_2"),
+    ];
+    for log in &logs {
+        logsdb.insert_log(log).await.unwrap();
+    }
+
+    check_sub_second_order(&logsdb).await;
+}
+
+#[tokio::test]
+async fn migration_normalises_existing_dates() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+    /* Stop before the datetime migration and store the dates like the text column did */
+    let mut migrator = sqlx::migrate!("./migrations/sqlite");
+    migrator.migrations = migrator.migrations[..2].to_vec().into();
+    migrator.run(&pool).await.unwrap();
+
+    for (id, created_at, target_id) in [
+        ("whole", "2024-01-01T00:00:00Z", "usr_1"),
+        ("half", "2024-01-01T00:00:00.5Z", "usr_2"),
+    ] {
+        sqlx::query(
+            r"INSERT INTO logs (id, created_at, group_id, actor_id, target_id, event_type,
+                description, data) VALUES (?, ?, 'grp_1', 'usr_staff', ?, ?, '', '{}')",
+        )
+        .bind(id)
+        .bind(created_at)
+        .bind(target_id)
+        .bind(EventType::Ban.as_str())
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    sqlx::migrate!("./migrations/sqlite").run(&pool).await.unwrap();
+
+    check_sub_second_order(&LogsDB::Sqlite(pool)).await;
+}
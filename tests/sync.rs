@@ -0,0 +1,43 @@
+mod common;
+
+use color_eyre::eyre::eyre;
+use common::{ids, log, Backends};
+use serde_json::json;
+use time::macros::datetime;
+use vrc_ban::{event::EventType, logsdb::Quarantined, sync};
+
+#[tokio::test]
+async fn ingest_counts_new_and_existing_logs() {
+    let at = datetime!(2024-01-01 0:00 UTC);
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        let stored = log("a", at, EventType::Ban, "usr_1");
+        logsdb.insert_log(&stored).await.unwrap();
+
+        let logs = vec![
+            log("a", at, EventType::Ban, "usr_1"),
+            log("b", at, EventType::Kick, "usr_2"),
+            log("b", at, EventType::Kick, "usr_2"),
+        ];
+        let (stats, new) = sync::ingest(logsdb, logs, &[]).await;
+
+        assert_eq!((stats.new, stats.existing, stats.failed), (1, 2, 0), "{name}");
+        assert_eq!(ids(&new), ["b"], "{name}");
+    }
+}
+
+#[tokio::test]
+async fn ingest_quarantines_an_entry_once() {
+    let entry = json!({ "id": "gal_1", "eventType": 1 });
+    let quarantined = [Quarantined::new("grp_1", entry, &eyre!("invalid type"))];
+
+    for (name, logsdb) in Backends::new().await.iter() {
+        let (stats, _) = sync::ingest(logsdb, Vec::new(), &quarantined).await;
+        assert_eq!((stats.quarantined, stats.existing), (1, 0), "{name}");
+
+        /* Still inside the incremental window on the next sync */
+        let (stats, _) = sync::ingest(logsdb, Vec::new(), &quarantined).await;
+        assert_eq!((stats.quarantined, stats.existing), (0, 1), "{name}");
+        assert_eq!(logsdb.count_quarantined().await.unwrap(), 1, "{name}");
+    }
+}